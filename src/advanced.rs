@@ -0,0 +1,393 @@
+use dialoguer::{Confirm, Input, MultiSelect, Select};
+use hex::encode;
+
+use crate::algorithms::{self, digest_bytes, hash_bytes, menu_algorithms, read_file, select_algorithm};
+use crate::cbor::canonical_cbor;
+use crate::input::read_text_or_file;
+
+/// Blake2b's salt and personalization fields are 16 bytes each.
+const BLAKE2B_PARAM_LEN: usize = 16;
+
+pub fn advanced_algorithms() {
+    #[allow(unused_mut)]
+    let mut advanced_choices = vec![
+        "Blake2b with Salt & Personalization",
+        "Combined Multi-Algorithm Fingerprint",
+        "Canonical CBOR Hash",
+        "Hash Chain Across Algorithms",
+        "CSV Column Hash",
+        "SipHash-2-4 Keyed Hash",
+        "Name-Based UUID (v5)",
+        "NDJSON Export Hash (Order-Insensitive)",
+        "Certificate Transparency Inclusion Proof",
+    ];
+    #[cfg(feature = "pdf")]
+    advanced_choices.push("PDF Text Content Hash");
+    let advanced_selection = Select::new()
+        .with_prompt("Choose an advanced mode")
+        .items(&advanced_choices)
+        .default(0)
+        .interact()
+        .unwrap();
+
+    match advanced_selection {
+        0 => blake2b_salt_personal(),
+        1 => combined_fingerprint(),
+        2 => canonical_cbor_hash(),
+        3 => hash_chain(),
+        4 => csv_column_hash(),
+        5 => crate::siphash::siphash_interactive(),
+        6 => crate::uuidv5::uuid_v5_interactive(),
+        7 => ndjson_export_hash(),
+        8 => crate::merkle::inclusion_proof_interactive(),
+        #[cfg(feature = "pdf")]
+        9 => pdf_text_hash(),
+        _ => unreachable!(),
+    }
+}
+
+/// Blake2b over the text with the salt and personalization fields of its
+/// parameter block filled in, giving domain-separated digests: the same
+/// input hashed for two different purposes produces unrelated outputs.
+///
+/// Unlike a Blake2 key, neither value is secret. A key is mixed in as an
+/// extra first block and turns Blake2 into a MAC; salt and personalization
+/// only change the initial state, so anyone who knows them can recompute the
+/// digest.
+fn blake2b_salt_personal() {
+    let input: String = Input::new()
+        .with_prompt("Enter text to hash")
+        .allow_empty(true)
+        .interact_text()
+        .unwrap();
+
+    let salt = read_param("Salt (up to 16 bytes, empty for none)");
+    let personal = read_param("Personalization (up to 16 bytes, empty for none)");
+
+    let digest = blake2b_simd::Params::new()
+        .salt(&salt)
+        .personal(&personal)
+        .hash(input.as_bytes());
+
+    println!("\nInput: '{}'", input);
+    println!("Salt:            {}", encode(padded(&salt)));
+    println!("Personalization: {}", encode(padded(&personal)));
+    println!("Output Hash: {}\n", digest.to_hex());
+    println!("With both fields empty this is the same as plain Blake2b.\n");
+}
+
+/// The digests of several algorithms concatenated into one fingerprint. A
+/// collision in the combination needs the same pair of inputs to collide
+/// under every component at once, so it holds as long as any one of them
+/// does. It is no stronger than its best component, though, and finding
+/// multi-collisions in iterated hashes is cheaper than the sum of the parts.
+fn combined_fingerprint() {
+    let (label, data) = match read_text_or_file() {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return;
+        }
+    };
+
+    let algorithms = menu_algorithms();
+    if algorithms.len() < 2 {
+        eprintln!("Error: at least two algorithms must be available in the menu");
+        return;
+    }
+
+    // Preselect the first two so plain enter gives a sensible combination.
+    let defaults: Vec<bool> = (0..algorithms.len()).map(|index| index < 2).collect();
+    let selected = loop {
+        let selected = MultiSelect::new()
+            .with_prompt("Choose algorithms to combine (space to toggle, enter to confirm)")
+            .items(algorithms)
+            .defaults(&defaults)
+            .interact()
+            .unwrap();
+        if selected.len() >= 2 {
+            break selected;
+        }
+        eprintln!("Error: choose at least two algorithms");
+    };
+
+    let components: Result<Vec<(&str, String)>, String> = selected
+        .iter()
+        .map(|&index| hash_bytes(&data, algorithms[index]).map(|digest| (algorithms[index], digest)))
+        .collect();
+    let components = match components {
+        Ok(components) => components,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return;
+        }
+    };
+
+    println!("\nInput: {}", label);
+    println!("Combination: {}", components.iter().map(|(algorithm, _)| *algorithm).collect::<Vec<_>>().join(" || "));
+    println!();
+
+    let mut offset = 0;
+    for (algorithm, digest) in &components {
+        println!("  hex {:>4}..{:<4} {:<12} {}", offset, offset + digest.len(), algorithm, digest);
+        offset += digest.len();
+    }
+    println!();
+
+    let digests: Vec<&str> = components.iter().map(|(_, digest)| digest.as_str()).collect();
+    println!("Combined Fingerprint ({} bits): {}", digests.concat().len() * 4, digests.concat());
+    println!("With boundaries: {}\n", digests.join("|"));
+}
+
+/// Hashes the deterministic re-encoding of a CBOR item rather than its raw
+/// bytes, so encoders that differ in integer widths, map order or
+/// indefinite lengths still agree on the digest.
+fn canonical_cbor_hash() {
+    let source_choices = vec!["Hex", "File"];
+    let source = Select::new()
+        .with_prompt("CBOR input source")
+        .items(&source_choices)
+        .default(0)
+        .interact()
+        .unwrap();
+
+    let input = match source {
+        0 => {
+            let hex_input: String = Input::new()
+                .with_prompt("CBOR bytes in hex")
+                .interact_text()
+                .unwrap();
+            let hex_input: String = hex_input.split_whitespace().collect();
+            hex::decode(hex_input.strip_prefix("0x").unwrap_or(&hex_input)).map_err(|e| format!("invalid hex: {}", e).into())
+        }
+        1 => {
+            let path: String = Input::new()
+                .with_prompt("Enter CBOR file path")
+                .interact_text()
+                .unwrap();
+            read_file(&path)
+        }
+        _ => unreachable!(),
+    };
+
+    let input = match input {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return;
+        }
+    };
+    let canonical = match canonical_cbor(&input) {
+        Ok(canonical) => canonical,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return;
+        }
+    };
+
+    let algorithm = select_algorithm();
+
+    println!("\nInput: {} bytes", input.len());
+    println!("Canonical encoding: {} bytes{}", canonical.len(), if canonical == input { " (input was already canonical)" } else { "" });
+    if canonical.len() <= 64 {
+        println!("  {}", encode(&canonical));
+    }
+    println!("Algorithm: {}", algorithm);
+    match (hash_bytes(&input, algorithm), hash_bytes(&canonical, algorithm)) {
+        (Ok(raw), Ok(canonical)) => {
+            println!("Raw Hash:       {}", raw);
+            println!("Canonical Hash: {}\n", canonical);
+        }
+        (Err(e), _) | (_, Err(e)) => eprintln!("Error: {}", e),
+    }
+}
+
+/// Applies a list of algorithms in turn, each hashing the raw digest bytes of
+/// the one before. Bitcoin's double SHA-256 is `SHA-256,SHA-256`. Only
+/// algorithms in the registry can be chained, so constructions built on
+/// others, like Bitcoin's HASH160 with its RIPEMD-160 step, can't be.
+fn hash_chain() {
+    let (label, data) = match read_text_or_file() {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return;
+        }
+    };
+
+    let chain: String = Input::new()
+        .with_prompt("Chain of algorithms, comma-separated")
+        .default("SHA-256,SHA-256".to_string())
+        .validate_with(|chain: &String| parse_chain(chain).map(|_| ()))
+        .interact_text()
+        .unwrap();
+    let chain = parse_chain(&chain).unwrap();
+
+    println!("\nInput: {}", label);
+    println!("Chain: {}", chain.join(" -> "));
+    println!();
+
+    let mut current = data;
+    for (step, algorithm) in chain.iter().enumerate() {
+        current = match digest_bytes(&current, algorithm) {
+            Ok(digest) => digest,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return;
+            }
+        };
+        println!("  {}. {:<12} {}", step + 1, algorithm, encode(&current));
+    }
+
+    println!("\nFinal Hash: {}", encode(&current));
+    println!("Each step hashes the previous digest's raw bytes, not its hex text.\n");
+}
+
+#[cfg(feature = "pdf")]
+fn pdf_text_hash() {
+    let path: String = Input::new()
+        .with_prompt("Enter PDF file path")
+        .interact_text()
+        .unwrap();
+
+    let algorithm = select_algorithm();
+
+    match crate::document::pdf_text_digest(&path, algorithm) {
+        Ok((digest, text)) => {
+            let preview: String = text.chars().take(60).collect();
+            println!("\nFile: '{}'", path);
+            println!("Algorithm: {}", algorithm);
+            println!("Text: {} characters, starting \"{}\"", text.chars().count(), preview);
+            println!("Content Hash: {}\n", digest);
+            println!("This hashes the extracted text only, not the file's bytes: PDFs with the");
+            println!("same words match even if their layout or metadata differ.\n");
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+        }
+    }
+}
+
+/// Rows of per-row digests printed before the rest are summarized.
+const CSV_ROWS_SHOWN: usize = 50;
+
+fn csv_column_hash() {
+    let path: String = Input::new()
+        .with_prompt("Enter CSV file path")
+        .interact_text()
+        .unwrap();
+    let has_headers = Confirm::new()
+        .with_prompt("Does the first row hold column names?")
+        .default(true)
+        .interact()
+        .unwrap();
+    let column: String = Input::new()
+        .with_prompt(if has_headers { "Column name or number (from 1)" } else { "Column number (from 1)" })
+        .interact_text()
+        .unwrap();
+    let mode_choices = vec!["One digest of the whole column", "One digest per row"];
+    let per_row = Select::new()
+        .with_prompt("Digest mode")
+        .items(&mode_choices)
+        .default(0)
+        .interact()
+        .unwrap()
+        == 1;
+
+    let algorithm = select_algorithm();
+
+    match crate::tabular::column_digest(&path, &column, has_headers, algorithm) {
+        Ok(result) => {
+            println!("\nFile: '{}'", path);
+            println!("Column: {}", result.column);
+            println!("Algorithm: {}", algorithm);
+            println!("Rows: {} ({} missing the column)", result.rows.len(), result.missing());
+            if per_row {
+                println!();
+                for (row, digest) in result.rows.iter().enumerate().take(CSV_ROWS_SHOWN) {
+                    println!("  {:>6}  {}", row + 1, digest.as_deref().unwrap_or("(missing)"));
+                }
+                if result.rows.len() > CSV_ROWS_SHOWN {
+                    println!("  ... {} more", result.rows.len() - CSV_ROWS_SHOWN);
+                }
+                println!();
+            } else {
+                println!("Column Hash: {}\n", result.digest);
+                println!("Each value is hashed with its length in front, so moving text between");
+                println!("adjacent cells changes the digest; other columns don't affect it.\n");
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+        }
+    }
+}
+
+fn ndjson_export_hash() {
+    let path: String = Input::new()
+        .with_prompt("Enter NDJSON file path")
+        .interact_text()
+        .unwrap();
+    let key: String = Input::new()
+        .with_prompt("Sort key (field name, or JSON Pointer like /meta/id)")
+        .default("id".to_string())
+        .interact_text()
+        .unwrap();
+
+    let algorithm = select_algorithm();
+
+    match crate::dataset::ndjson_digest(&path, &key, algorithm) {
+        Ok(result) => {
+            println!("\nFile: '{}'", path);
+            println!("Sort key: {}", key);
+            println!("Algorithm: {}", algorithm);
+            println!("Records: {}", result.records);
+            if result.runs > 0 {
+                println!("Sorted on disk in {} runs", result.runs);
+            }
+            if result.duplicate_keys > 0 {
+                println!("Warning: {} records repeat the previous record's key; it is not unique", result.duplicate_keys);
+            }
+            println!("Dataset Hash: {}\n", result.digest);
+            println!("Records are canonicalized and sorted before hashing, so dumps of the same");
+            println!("rows match regardless of export order, key order or whitespace.\n");
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+        }
+    }
+}
+
+fn parse_chain(chain: &str) -> Result<Vec<&'static str>, String> {
+    let chain = chain
+        .split(',')
+        .map(|name| algorithms::lookup(name.trim()))
+        .collect::<Result<Vec<_>, _>>()?;
+    if chain.is_empty() {
+        return Err("the chain needs at least one algorithm".into());
+    }
+    Ok(chain)
+}
+
+/// Reads a parameter string, truncated to the 16 bytes Blake2b allows.
+/// Shorter values are zero-padded by the parameter block itself.
+fn read_param(prompt: &str) -> Vec<u8> {
+    let value: String = Input::new()
+        .with_prompt(prompt)
+        .allow_empty(true)
+        .interact_text()
+        .unwrap();
+
+    let mut bytes = value.into_bytes();
+    if bytes.len() > BLAKE2B_PARAM_LEN {
+        println!("Truncated to the first {} bytes.", BLAKE2B_PARAM_LEN);
+        bytes.truncate(BLAKE2B_PARAM_LEN);
+    }
+    bytes
+}
+
+fn padded(bytes: &[u8]) -> [u8; BLAKE2B_PARAM_LEN] {
+    let mut block = [0u8; BLAKE2B_PARAM_LEN];
+    block[..bytes.len()].copy_from_slice(bytes);
+    block
+}
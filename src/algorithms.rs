@@ -0,0 +1,500 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::Duration;
+use sha2::{Sha256, Sha512_224, Digest as _};
+use blake2::Blake2b512;
+use tiny_keccak::{Hasher, Keccak};
+use dialoguer::Select;
+use hex::encode;
+
+#[cfg(feature = "plugins")]
+use crate::plugin::{self, Plugin};
+
+/// Every algorithm compiled into this build, in menu order. Algorithms
+/// behind a cargo feature only appear here when that feature is enabled.
+/// External commands registered as plugins are added by `registry`.
+pub const ALGORITHMS: &[&str] = &[
+    "SHA-256",
+    "SHA-512/224",
+    "Keccak-256",
+    "Blake2b",
+    #[cfg(feature = "md5")]
+    "MD5",
+    "FarmHash64",
+    "CRC32",
+];
+
+/// The built-in algorithms followed by any plugins, which `--algo` and the
+/// menus accept alike. Plugins must be loaded before the first call.
+pub fn registry() -> &'static [&'static str] {
+    static REGISTRY: OnceLock<Vec<&'static str>> = OnceLock::new();
+
+    REGISTRY.get_or_init(|| {
+        #[allow(unused_mut)]
+        let mut registry = ALGORITHMS.to_vec();
+        #[cfg(feature = "plugins")]
+        registry.extend(plugin::plugins().iter().map(|plugin| plugin.name));
+        registry
+    })
+}
+
+/// Resolves a user-supplied name (case-insensitive) to its registry entry.
+pub fn lookup(name: &str) -> Result<&'static str, String> {
+    registry()
+        .iter()
+        .find(|algorithm| algorithm.eq_ignore_ascii_case(name))
+        .copied()
+        .ok_or_else(|| {
+            format!(
+                "algorithm '{}' not available in this build; available: {}",
+                name,
+                registry().join(", ")
+            )
+        })
+}
+
+/// An algorithm together with its parameters, parsed from a spec string.
+///
+/// Grammar, as accepted by `--algo`:
+///
+/// ```text
+/// spec   = name [ ":" length ]
+/// name   = an entry of ALGORITHMS, case-insensitive
+/// length = output length in bytes, in decimal
+/// ```
+///
+/// Only Blake2b takes a parameter: its output length, 1 to 64 bytes, so
+/// `blake2b:32` is BLAKE2b-256. A different length is not a truncation: the
+/// length is part of Blake2b's parameter block, so every length gives an
+/// unrelated digest. `blake2b:64` is plain Blake2b. Any other algorithm given
+/// a parameter is a malformed spec, and SHAKE and PBKDF2 are not in the
+/// registry, so `shake256:64` or `pbkdf2-sha256:600000` name unknown
+/// algorithms.
+///
+/// A seed, for the non-cryptographic hashes that take one, comes from
+/// `--seed` through `with_seed` rather than from the spec string.
+#[derive(Clone, Copy)]
+pub struct AlgorithmSpec {
+    pub algorithm: &'static str,
+    pub output_len: Option<usize>,
+    pub seed: Option<u64>,
+}
+
+impl AlgorithmSpec {
+    pub fn plain(algorithm: &'static str) -> Self {
+        AlgorithmSpec { algorithm, output_len: None, seed: None }
+    }
+
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (name, parameter) = match spec.split_once(':') {
+            Some((name, parameter)) => (name, Some(parameter)),
+            None => (spec, None),
+        };
+        let algorithm = lookup(name.trim())?;
+        let Some(parameter) = parameter else {
+            return Ok(AlgorithmSpec::plain(algorithm));
+        };
+
+        let max_len = match algorithm {
+            "Blake2b" => 64,
+            _ => return Err(format!("malformed spec '{}': {} takes no parameters", spec, algorithm)),
+        };
+        let output_len: usize = parameter
+            .trim()
+            .parse()
+            .map_err(|_| format!("malformed spec '{}': '{}' is not an output length in bytes", spec, parameter))?;
+        if !(1..=max_len).contains(&output_len) {
+            return Err(format!("malformed spec '{}': {} output length must be 1 to {} bytes", spec, algorithm, max_len));
+        }
+        Ok(AlgorithmSpec { algorithm, output_len: Some(output_len), seed: None })
+    }
+
+    /// Seeds the hash, for reproducing the values of hash tables and other
+    /// systems that seed their hash function. Each algorithm reads the seed
+    /// its own way:
+    ///
+    /// - FarmHash64: the 64-bit seed of FarmHash's `Hash64WithSeed`. That is
+    ///   a different function from the unseeded Fingerprint64, so seed 0 does
+    ///   not give the unseeded digest, and Google doesn't promise its values
+    ///   stay the same across FarmHash versions.
+    /// - CRC32: the initial CRC register, as in zlib's `crc32(crc, buf, len)`.
+    ///   The seed is the CRC of data hashed before, so seeding with the CRC
+    ///   of A and hashing B gives the CRC of A followed by B; seed 0 is
+    ///   plain CRC32. It must fit in 32 bits.
+    ///
+    /// Cryptographic hashes take no seed; use a salt or a MAC for those.
+    pub fn with_seed(self, seed: u64) -> Result<Self, String> {
+        match self.algorithm {
+            "FarmHash64" => {}
+            "CRC32" if seed <= u32::MAX as u64 => {}
+            "CRC32" => return Err(format!("seed {:#x} does not fit CRC32's 32-bit register", seed)),
+            algorithm => {
+                return Err(format!("{} takes no seed; seeds apply to {}", algorithm, SEEDED_ALGORITHMS.join(" and ")));
+            }
+        }
+        Ok(AlgorithmSpec { seed: Some(seed), ..self })
+    }
+
+    pub fn is_plain(&self) -> bool {
+        self.output_len.is_none() && self.seed.is_none()
+    }
+
+    pub fn hasher(&self) -> StreamHasher {
+        match (self.algorithm, self.output_len, self.seed) {
+            ("Blake2b", Some(len), _) => StreamHasher::Blake2bVar(blake2b_simd::Params::new().hash_length(len).to_state()),
+            ("FarmHash64", _, Some(seed)) => StreamHasher::FarmHash64Seeded(Vec::new(), seed),
+            ("CRC32", _, Some(seed)) => StreamHasher::Crc32(crc32fast::Hasher::new_with_initial(seed as u32)),
+            (algorithm, _, _) => StreamHasher::new(algorithm),
+        }
+    }
+
+    pub fn hash_bytes(&self, input: &[u8]) -> Result<String, String> {
+        let mut hasher = self.hasher();
+        hasher.update(input);
+        hasher.finalize().map(encode)
+    }
+}
+
+impl std::fmt::Display for AlgorithmSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.output_len {
+            Some(len) => write!(f, "{}:{}", self.algorithm, len)?,
+            None => write!(f, "{}", self.algorithm)?,
+        }
+        match self.seed {
+            Some(seed) => write!(f, " (seed {:#x})", seed),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Algorithms that accept `--seed`; see `AlgorithmSpec::with_seed`.
+pub const SEEDED_ALGORITHMS: &[&str] = &["FarmHash64", "CRC32"];
+
+/// Parses a seed as a decimal integer, or hexadecimal with a `0x` prefix.
+pub fn parse_seed(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let parsed = match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => value.parse(),
+    };
+    parsed.map_err(|_| format!("'{}' is not a 64-bit seed in decimal or 0x-prefixed hex", value))
+}
+
+/// Environment variable naming the default algorithm, for when `--algo`
+/// isn't given. It also preselects the entry in interactive menus.
+pub const ALGO_ENV: &str = "HASHING_DEMO_ALGO";
+
+/// The algorithm named by `HASHING_DEMO_ALGO`, if set. An unknown name is
+/// reported once and then ignored.
+fn env_algorithm() -> Option<&'static str> {
+    static ENV_ALGORITHM: OnceLock<Option<&'static str>> = OnceLock::new();
+
+    *ENV_ALGORITHM.get_or_init(|| {
+        let name = std::env::var(ALGO_ENV).ok().filter(|name| !name.trim().is_empty())?;
+        match lookup(name.trim()) {
+            Ok(algorithm) => Some(algorithm),
+            Err(e) => {
+                eprintln!("Warning: ignoring {}: {}", ALGO_ENV, e);
+                None
+            }
+        }
+    })
+}
+
+/// `HASHING_DEMO_ALGO` if it names a valid algorithm, else the first in the
+/// registry. `--algo` takes precedence over both.
+pub fn default_algorithm() -> &'static str {
+    env_algorithm().unwrap_or(ALGORITHMS[0])
+}
+
+static MENU_ALGORITHMS: OnceLock<Vec<&'static str>> = OnceLock::new();
+
+/// Limits the interactive menus to the named algorithms, kept in registry
+/// order. Unknown names are reported and skipped; if none are left the menus
+/// keep offering everything.
+pub fn set_menu_algorithms(names: &[String]) {
+    let mut menu = Vec::new();
+    for name in names.iter().map(|name| name.trim()).filter(|name| !name.is_empty()) {
+        match lookup(name) {
+            Ok(algorithm) => menu.push(algorithm),
+            Err(e) => eprintln!("Warning: ignoring entry in --menu-algos: {}", e),
+        }
+    }
+
+    if menu.is_empty() {
+        eprintln!("Warning: --menu-algos names no usable algorithm; showing all of them");
+        return;
+    }
+
+    let menu = registry().iter().copied().filter(|algorithm| menu.contains(algorithm)).collect();
+    let _ = MENU_ALGORITHMS.set(menu);
+}
+
+/// The algorithms offered in interactive menus: everything unless narrowed
+/// with `--menu-algos`.
+pub fn menu_algorithms() -> &'static [&'static str] {
+    MENU_ALGORITHMS.get().map(|menu| menu.as_slice()).unwrap_or(registry())
+}
+
+pub fn select_algorithm() -> &'static str {
+    let menu = menu_algorithms();
+    let default = default_algorithm();
+    let selection = Select::new()
+        .with_prompt("Choose a hashing algorithm")
+        .items(menu)
+        .default(menu.iter().position(|algorithm| *algorithm == default).unwrap_or(0))
+        .interact()
+        .unwrap();
+
+    menu[selection]
+}
+
+/// Input block size in bytes: the compression-function block for
+/// Merkle–Damgård designs, the sponge rate for Keccak.
+pub fn block_size(algorithm: &str) -> usize {
+    match algorithm {
+        "SHA-256" => 64,
+        "SHA-512/224" => 128,
+        "Keccak-256" => 136,
+        "Blake2b" => 128,
+        "MD5" => 64,
+        "FarmHash64" => 64,
+        // Table-driven CRC32 has no real block; this is the unit crc32fast folds.
+        "CRC32" => 16,
+        // An external command's block is unknown; step through 64 bytes.
+        #[cfg(feature = "plugins")]
+        name if plugin::find(name).is_some() => 64,
+        _ => unreachable!(),
+    }
+}
+
+/// SHA-256 initial hash value H(0) (FIPS 180-4, section 5.3.3).
+pub const SHA256_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a,
+    0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// The padding SHA-256 appends to a message of `len` bytes: 0x80, zeros up
+/// to 56 mod 64, then the length in bits as a big-endian u64.
+pub fn sha256_padding(len: u64) -> Vec<u8> {
+    let mut padding = vec![0x80];
+    padding.resize((119 - len as usize % 64) % 64 + 1, 0);
+    padding.extend_from_slice(&(len * 8).to_be_bytes());
+    padding
+}
+
+/// An in-progress hash that can be fed in pieces. Cloning it and finalizing
+/// the clone gives a snapshot digest of everything fed so far.
+#[derive(Clone)]
+pub enum StreamHasher {
+    Sha256(Sha256),
+    Sha512_224(Sha512_224),
+    Keccak256(Keccak),
+    Blake2b(Blake2b512),
+    /// Blake2b with a non-default output length, from an `AlgorithmSpec`.
+    Blake2bVar(blake2b_simd::State),
+    #[cfg(feature = "md5")]
+    Md5(md5::Context),
+    /// FarmHash has no streaming API, so input is buffered until finalize.
+    FarmHash64(Vec<u8>),
+    /// FarmHash64 under a seed, buffered the same way.
+    FarmHash64Seeded(Vec<u8>, u64),
+    Crc32(crc32fast::Hasher),
+    /// An external command, run once on all the input at finalize, which
+    /// fails if the command does.
+    #[cfg(feature = "plugins")]
+    Plugin(&'static Plugin, Vec<u8>),
+}
+
+impl StreamHasher {
+    pub fn new(algorithm: &str) -> Self {
+        match algorithm {
+            "SHA-256" => StreamHasher::Sha256(Sha256::new()),
+            "SHA-512/224" => StreamHasher::Sha512_224(Sha512_224::new()),
+            "Keccak-256" => StreamHasher::Keccak256(Keccak::v256()),
+            "Blake2b" => StreamHasher::Blake2b(Blake2b512::new()),
+            #[cfg(feature = "md5")]
+            "MD5" => StreamHasher::Md5(md5::Context::new()),
+            "FarmHash64" => StreamHasher::FarmHash64(Vec::new()),
+            "CRC32" => StreamHasher::Crc32(crc32fast::Hasher::new()),
+            #[cfg(feature = "plugins")]
+            name if plugin::find(name).is_some() => StreamHasher::Plugin(plugin::find(name).unwrap(), Vec::new()),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn update(&mut self, input: &[u8]) {
+        match self {
+            StreamHasher::Sha256(hasher) => hasher.update(input),
+            StreamHasher::Sha512_224(hasher) => hasher.update(input),
+            StreamHasher::Keccak256(keccak) => keccak.update(input),
+            StreamHasher::Blake2b(hasher) => hasher.update(input),
+            StreamHasher::Blake2bVar(state) => {
+                state.update(input);
+            }
+            #[cfg(feature = "md5")]
+            StreamHasher::Md5(context) => context.consume(input),
+            StreamHasher::FarmHash64(buffer) | StreamHasher::FarmHash64Seeded(buffer, _) => buffer.extend_from_slice(input),
+            StreamHasher::Crc32(hasher) => hasher.update(input),
+            #[cfg(feature = "plugins")]
+            StreamHasher::Plugin(_, buffer) => buffer.extend_from_slice(input),
+        }
+    }
+
+    /// The digest of everything fed in. Built-in algorithms always succeed;
+    /// a plugin fails if its command does.
+    pub fn finalize(self) -> Result<Vec<u8>, String> {
+        Ok(match self {
+            StreamHasher::Sha256(hasher) => hasher.finalize().to_vec(),
+            StreamHasher::Sha512_224(hasher) => hasher.finalize().to_vec(),
+            StreamHasher::Keccak256(keccak) => {
+                let mut output = [0u8; 32];
+                keccak.finalize(&mut output);
+                output.to_vec()
+            }
+            StreamHasher::Blake2b(hasher) => hasher.finalize().to_vec(),
+            StreamHasher::Blake2bVar(state) => state.finalize().as_bytes().to_vec(),
+            #[cfg(feature = "md5")]
+            StreamHasher::Md5(context) => context.compute().0.to_vec(),
+            // Fingerprint64 is the variant Google guarantees stable across
+            // platforms and versions; printed as a big-endian integer.
+            StreamHasher::FarmHash64(buffer) => farmhash::fingerprint64(&buffer).to_be_bytes().to_vec(),
+            StreamHasher::FarmHash64Seeded(buffer, seed) => farmhash::hash64_with_seed(&buffer, seed).to_be_bytes().to_vec(),
+            // The IEEE CRC-32 of zlib, PNG and ZIP, big-endian like `crc32`(1).
+            StreamHasher::Crc32(hasher) => hasher.finalize().to_be_bytes().to_vec(),
+            #[cfg(feature = "plugins")]
+            StreamHasher::Plugin(plugin, buffer) => plugin.digest(&buffer)?,
+        })
+    }
+}
+
+pub fn digest_bytes(input: &[u8], algorithm: &str) -> Result<Vec<u8>, String> {
+    let mut hasher = StreamHasher::new(algorithm);
+    hasher.update(input);
+    hasher.finalize()
+}
+
+pub fn hash_bytes(input: &[u8], algorithm: &str) -> Result<String, String> {
+    digest_bytes(input, algorithm).map(encode)
+}
+
+pub fn hash_text(input: &str, algorithm: &str) -> Result<String, String> {
+    hash_bytes(input.as_bytes(), algorithm)
+}
+
+/// Friendlier errors than `fs::read` gives for the common mistakes.
+pub fn check_file(file_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let path = Path::new(file_path);
+
+    if !path.exists() {
+        return Err(format!("File '{}' does not exist", file_path).into());
+    }
+
+    if !path.is_file() {
+        return Err(format!("'{}' is not a file", file_path).into());
+    }
+
+    Ok(())
+}
+
+/// Retries `hash_file` and `read_file` allow per read when `--retries`
+/// isn't given.
+pub const DEFAULT_READ_RETRIES: u32 = 3;
+
+/// Wait before the first retry of a read; each further retry doubles it.
+const RETRY_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Read size for `hash_file` and `read_file`.
+const FILE_CHUNK: usize = 64 * 1024;
+
+static READ_RETRIES: AtomicU32 = AtomicU32::new(DEFAULT_READ_RETRIES);
+static RETRIES_USED: AtomicU64 = AtomicU64::new(0);
+
+/// Sets how many times a transient read error is retried (`--retries`).
+pub fn set_read_retries(retries: u32) {
+    READ_RETRIES.store(retries, Ordering::Relaxed);
+}
+
+/// Retries made since the last call, for reporting after a hash.
+pub fn take_read_retries() -> u64 {
+    RETRIES_USED.swap(0, Ordering::Relaxed)
+}
+
+/// Errors worth another try on a network file system (NFS, SMB), where a
+/// read can time out or be interrupted and then succeed. Anything else,
+/// like a missing file or a permission error, won't fix itself.
+fn is_transient(kind: io::ErrorKind) -> bool {
+    matches!(kind, io::ErrorKind::Interrupted | io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut)
+}
+
+/// One `read`, retried with doubling backoff while it fails transiently, up
+/// to the configured number of retries.
+fn read_with_retry(reader: &mut impl Read, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut retries = 0;
+    let mut delay = RETRY_BACKOFF;
+    loop {
+        match reader.read(buffer) {
+            Ok(read) => return Ok(read),
+            Err(e) if is_transient(e.kind()) && retries < READ_RETRIES.load(Ordering::Relaxed) => {
+                retries += 1;
+                RETRIES_USED.fetch_add(1, Ordering::Relaxed);
+                thread::sleep(delay);
+                delay *= 2;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Calls `each` with successive chunks of a file, after the checks of
+/// `check_file`.
+pub fn read_chunks(file_path: &str, mut each: impl FnMut(&[u8])) -> Result<(), Box<dyn std::error::Error>> {
+    check_file(file_path)?;
+
+    let mut file = File::open(file_path)?;
+    let mut buffer = vec![0u8; FILE_CHUNK];
+    loop {
+        let read = read_with_retry(&mut file, &mut buffer).map_err(|e| format!("Cannot read '{}': {}", file_path, e))?;
+        if read == 0 {
+            return Ok(());
+        }
+        each(&buffer[..read]);
+    }
+}
+
+/// Reads a file for hashing, after the checks of `check_file`.
+pub fn read_file(file_path: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut data = Vec::new();
+    read_chunks(file_path, |chunk| data.extend_from_slice(chunk))?;
+    Ok(data)
+}
+
+pub fn hash_file(file_path: &str, algorithm: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let mut hasher = StreamHasher::new(algorithm);
+    read_chunks(file_path, |chunk| hasher.update(chunk))?;
+    Ok(encode(hasher.finalize()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha512_224_known_answer() {
+        // FIPS 180-4 example; not the first 28 bytes of SHA-512("abc").
+        assert_eq!(hash_text("abc", "SHA-512/224").unwrap(), "4634270f707b6a54daae7530460842e20e37ed265ceee9a43e8924aa");
+    }
+
+    #[test]
+    fn farmhash64_matches_reference_fingerprints() {
+        // The empty input hashes to FarmHash's k2 constant; "abc" is BigQuery's
+        // FARM_FINGERPRINT("abc") = 2640714258260161385.
+        assert_eq!(hash_text("", "FarmHash64").unwrap(), "9ae16a3b2f90404f");
+        assert_eq!(hash_text("abc", "FarmHash64").unwrap(), format!("{:016x}", 2640714258260161385u64));
+    }
+}
@@ -0,0 +1,182 @@
+use std::fs::File;
+use std::io::Read;
+use dialoguer::{Confirm, Input, Select};
+use hex::encode;
+
+use crate::algorithms::{hash_bytes, select_algorithm, StreamHasher};
+use crate::input::read_text_or_file;
+
+/// Window counts above this ask for confirmation before printing.
+const WINDOW_OUTPUT_WARN: usize = 10_000;
+
+/// Most common byte values listed in a histogram summary.
+const HISTOGRAM_TOP: usize = 8;
+
+pub fn analysis() {
+    let analysis_choices = vec!["Sliding Window Hashes", "Byte Histogram with Digest", "Content-Defined Chunking Dedup Report"];
+    let analysis_selection = Select::new()
+        .with_prompt("Choose an analysis mode")
+        .items(&analysis_choices)
+        .default(0)
+        .interact()
+        .unwrap();
+
+    match analysis_selection {
+        0 => sliding_windows(),
+        1 => byte_histogram(),
+        2 => crate::dedup::dedup_report(),
+        _ => unreachable!(),
+    }
+}
+
+/// Digests every window of W bytes, starting every S bytes, one line per
+/// window. Searching the output for the digest of a known block finds where
+/// it sits in a larger file, provided it starts on a multiple of the step.
+fn sliding_windows() {
+    let (label, data) = match read_text_or_file() {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return;
+        }
+    };
+
+    let window: usize = Input::new()
+        .with_prompt("Window size W in bytes")
+        .default(64)
+        .validate_with(|window: &usize| if *window > 0 { Ok(()) } else { Err("must be at least 1") })
+        .interact_text()
+        .unwrap();
+
+    let step: usize = Input::new()
+        .with_prompt("Step S in bytes")
+        .default(window)
+        .validate_with(|step: &usize| if *step > 0 { Ok(()) } else { Err("must be at least 1") })
+        .interact_text()
+        .unwrap();
+
+    if window > data.len() {
+        eprintln!("Error: window of {} bytes is larger than the {}-byte input", window, data.len());
+        return;
+    }
+
+    let count = (data.len() - window) / step + 1;
+    if count > WINDOW_OUTPUT_WARN {
+        let proceed = Confirm::new()
+            .with_prompt(format!("This prints {} digests; a larger step gives fewer. Continue?", count))
+            .default(false)
+            .interact()
+            .unwrap();
+        if !proceed {
+            return;
+        }
+    }
+
+    let algorithm = select_algorithm();
+
+    println!("\nInput: {} ({} bytes)", label, data.len());
+    println!("Algorithm: {}", algorithm);
+    println!("Windows: {} of {} bytes, every {} bytes\n", count, window, step);
+
+    let width = format!("{:x}", data.len()).len();
+    for start in (0..count).map(|i| i * step) {
+        match hash_bytes(&data[start..start + window], algorithm) {
+            Ok(hash) => println!("{:0width$x}  {}", start, hash, width = width),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return;
+            }
+        }
+    }
+    println!();
+}
+
+/// Hashes a file and counts each byte value in the same pass, then sums the
+/// counts up: the commonest bytes, how much is printable ASCII, and the
+/// Shannon entropy, which sits near 8 bits per byte for compressed or
+/// encrypted data and well below that for text.
+fn byte_histogram() {
+    let path: String = Input::new()
+        .with_prompt("Enter file path")
+        .interact_text()
+        .unwrap();
+
+    let algorithm = select_algorithm();
+
+    let (digest, counts) = match hash_with_histogram(&path, algorithm) {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return;
+        }
+    };
+
+    let total: u64 = counts.iter().sum();
+    println!("\nFile: '{}'", path);
+    println!("Algorithm: {}", algorithm);
+    println!("Output Hash: {}", digest);
+    println!("Size: {} bytes", total);
+    if total == 0 {
+        println!();
+        return;
+    }
+
+    let distinct = counts.iter().filter(|&&count| count > 0).count();
+    let printable: u64 = counts
+        .iter()
+        .enumerate()
+        .filter(|&(byte, _)| (0x20..0x7f).contains(&byte) || matches!(byte, 0x09 | 0x0a | 0x0d))
+        .map(|(_, count)| count)
+        .sum();
+    let entropy: f64 = counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum();
+
+    println!("Distinct byte values: {} of 256", distinct);
+    println!("Printable ASCII: {:.1}%", printable as f64 / total as f64 * 100.0);
+    println!("Entropy: {:.3} bits per byte", entropy);
+    println!();
+
+    let mut ranked: Vec<(usize, u64)> = counts.iter().copied().enumerate().filter(|&(_, count)| count > 0).collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    println!("Most common bytes:");
+    for &(byte, count) in ranked.iter().take(HISTOGRAM_TOP) {
+        let shown = if (0x21..0x7f).contains(&byte) { format!("'{}'", byte as u8 as char) } else { String::new() };
+        println!("  0x{:02x} {:<4} {:>12}  {:>5.1}%", byte, shown, count, count as f64 / total as f64 * 100.0);
+    }
+    println!();
+
+    let profile = if entropy > 7.9 {
+        "looks compressed, encrypted or random"
+    } else if printable as f64 / total as f64 > 0.95 {
+        "looks like text"
+    } else {
+        "looks like structured binary data"
+    };
+    println!("Profile: {} (a heuristic, not a file type check).\n", profile);
+}
+
+fn hash_with_histogram(path: &str, algorithm: &str) -> Result<(String, [u64; 256]), Box<dyn std::error::Error>> {
+    let mut file = File::open(path).map_err(|e| format!("Cannot open '{}': {}", path, e))?;
+    let mut hasher = StreamHasher::new(algorithm);
+    let mut counts = [0u64; 256];
+    let mut buffer = [0u8; 64 * 1024];
+
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        for &byte in &buffer[..read] {
+            counts[byte as usize] += 1;
+        }
+    }
+
+    Ok((encode(hasher.finalize()?), counts))
+}
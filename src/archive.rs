@@ -0,0 +1,209 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use dialoguer::{Input, Select};
+use hex::encode;
+
+use crate::algorithms::{select_algorithm, StreamHasher};
+
+pub fn archives() {
+    let archive_choices = vec!["Tar Logical Digest", "ZIP Logical Digest"];
+    let archive_selection = Select::new()
+        .with_prompt("Choose an archive mode")
+        .items(&archive_choices)
+        .default(0)
+        .interact()
+        .unwrap();
+
+    match archive_selection {
+        0 => tar_digest_interactive(),
+        1 => zip_digest_interactive(),
+        _ => unreachable!(),
+    }
+}
+
+/// What an archive entry contributes to the logical digest.
+#[derive(Clone, Copy)]
+enum EntryKind {
+    File = 0,
+    Symlink = 1,
+    Hardlink = 2,
+}
+
+struct LogicalEntry {
+    kind: EntryKind,
+    /// Content digest for files, the link target for links.
+    payload: Vec<u8>,
+}
+
+fn tar_digest_interactive() {
+    let path: String = Input::new()
+        .with_prompt("Enter tar file path (uncompressed .tar)")
+        .interact_text()
+        .unwrap();
+
+    let algorithm = select_algorithm();
+
+    match tar_logical_digest(&path, algorithm) {
+        Ok((digest, entries)) => {
+            println!("\nArchive: '{}'", path);
+            println!("Algorithm: {}", algorithm);
+            println!("Entries: {}", entries);
+            println!("Logical Digest: {}\n", digest);
+            println!("Only entry paths, file contents and link targets are hashed; timestamps,");
+            println!("owners, permissions and entry order are ignored.\n");
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+        }
+    }
+}
+
+/// A digest of a tar archive's logical contents, stable across archives that
+/// differ only in metadata or entry order.
+///
+/// Normalization:
+/// - Included: each entry's path, the contents of regular files, and the
+///   target of symbolic and hard links, tagged with which of the three it is.
+/// - Excluded: mtime, uid/gid, user/group names, mode bits, device numbers,
+///   PAX/GNU extension records and the header format itself.
+/// - Directory entries are skipped, since file paths already imply them.
+/// - Paths lose any leading `./` and trailing `/`; when a path occurs more
+///   than once the last entry wins, as it would on extraction.
+/// - Entries are sorted by path, and the digest covers, for each entry,
+///   `kind || len(path) || path || len(payload) || payload` with lengths as
+///   big-endian u64, where the payload is the file's own digest or the link
+///   target.
+///
+/// Returns the hex digest and the number of entries it covers.
+pub fn tar_logical_digest(path: &str, algorithm: &str) -> Result<(String, usize), Box<dyn std::error::Error>> {
+    let file = File::open(path).map_err(|e| format!("Cannot open '{}': {}", path, e))?;
+    let mut archive = tar::Archive::new(file);
+    let mut entries = BTreeMap::new();
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let entry_path = normalize_path(&entry.path()?.to_string_lossy());
+        let entry_type = entry.header().entry_type();
+
+        let logical = if entry_type.is_file() {
+            LogicalEntry { kind: EntryKind::File, payload: digest_reader(&mut entry, algorithm)? }
+        } else if entry_type.is_symlink() || entry_type.is_hard_link() {
+            let target = entry.link_name()?.map(|t| t.to_string_lossy().into_owned()).unwrap_or_default();
+            let kind = if entry_type.is_symlink() { EntryKind::Symlink } else { EntryKind::Hardlink };
+            LogicalEntry { kind, payload: normalize_path(&target).into_bytes() }
+        } else {
+            continue;
+        };
+
+        entries.insert(entry_path, logical);
+    }
+
+    Ok((logical_digest(&entries, algorithm)?, entries.len()))
+}
+
+fn zip_digest_interactive() {
+    let path: String = Input::new()
+        .with_prompt("Enter ZIP file path")
+        .interact_text()
+        .unwrap();
+
+    let algorithm = select_algorithm();
+
+    match zip_logical_digest(&path, algorithm) {
+        Ok((digest, entries)) => {
+            println!("\nArchive: '{}'", path);
+            println!("Algorithm: {}", algorithm);
+            println!("Entries: {}", entries);
+            println!("Logical Digest: {}\n", digest);
+            println!("Only entry names, uncompressed contents and symlink targets are hashed;");
+            println!("timestamps, compression, permissions, comments and entry order are ignored.\n");
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+        }
+    }
+}
+
+/// The ZIP counterpart of `tar_logical_digest`, hashed the same way, so a
+/// ZIP and a tar of the same tree get the same digest.
+///
+/// Normalization:
+/// - Included: each entry's name, the uncompressed contents of files, and
+///   the target of symbolic links (stored as the entry's contents, with the
+///   link bit in the Unix mode).
+/// - Excluded: modification times (DOS and extended-timestamp fields),
+///   compression method and level, compressed sizes, CRC-32s, version and
+///   flag fields, internal and external attributes other than the link bit,
+///   extra fields, per-entry and archive comments, and the order and offsets
+///   of entries in the file.
+/// - Directory entries are skipped, and names are normalized as for tar.
+///
+/// Encrypted entries are an error rather than being skipped, since leaving
+/// them out would give a digest that doesn't cover the whole archive.
+pub fn zip_logical_digest(path: &str, algorithm: &str) -> Result<(String, usize), Box<dyn std::error::Error>> {
+    let file = File::open(path).map_err(|e| format!("Cannot open '{}': {}", path, e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("'{}' is not a readable ZIP archive: {}", path, e))?;
+    let mut entries = BTreeMap::new();
+
+    for index in 0..archive.len() {
+        let raw = archive.by_index_raw(index)?;
+        if raw.encrypted() {
+            return Err(format!("entry '{}' is encrypted; encrypted entries can't be hashed", raw.name()).into());
+        }
+        drop(raw);
+
+        let mut entry = archive.by_index(index)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let entry_path = normalize_path(entry.name());
+
+        let logical = if entry.is_symlink() {
+            let mut target = String::new();
+            entry.read_to_string(&mut target)?;
+            LogicalEntry { kind: EntryKind::Symlink, payload: normalize_path(&target).into_bytes() }
+        } else {
+            LogicalEntry { kind: EntryKind::File, payload: digest_reader(&mut entry, algorithm)? }
+        };
+
+        entries.insert(entry_path, logical);
+    }
+
+    Ok((logical_digest(&entries, algorithm)?, entries.len()))
+}
+
+/// `kind || len(path) || path || len(payload) || payload` for each entry in
+/// path order.
+fn logical_digest(entries: &BTreeMap<String, LogicalEntry>, algorithm: &str) -> Result<String, String> {
+    let mut hasher = StreamHasher::new(algorithm);
+    for (entry_path, entry) in entries {
+        hasher.update(&[entry.kind as u8]);
+        hasher.update(&(entry_path.len() as u64).to_be_bytes());
+        hasher.update(entry_path.as_bytes());
+        hasher.update(&(entry.payload.len() as u64).to_be_bytes());
+        hasher.update(&entry.payload);
+    }
+    hasher.finalize().map(encode)
+}
+
+fn digest_reader(reader: &mut impl Read, algorithm: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut hasher = StreamHasher::new(algorithm);
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher.finalize()?)
+}
+
+fn normalize_path(path: &str) -> String {
+    let mut path = path;
+    while let Some(rest) = path.strip_prefix("./") {
+        path = rest;
+    }
+    path.trim_end_matches('/').to_string()
+}
@@ -0,0 +1,36 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turns on audit records for every hash the user sees (`--syslog`).
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Emits one audit record at NOTICE severity. Goes to syslog when built with
+/// the `syslog` feature and a local syslog socket is reachable, otherwise to
+/// stderr so the record is never silently dropped.
+pub fn record(algorithm: &str, input: &str, digest: &str) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+
+    let message = format!("hash algorithm=\"{}\" input={:?} digest={}", algorithm, input, digest);
+
+    #[cfg(feature = "syslog")]
+    {
+        let formatter = syslog::Formatter3164 {
+            facility: syslog::Facility::LOG_USER,
+            hostname: None,
+            process: "hashing-demo".into(),
+            pid: std::process::id(),
+        };
+        if let Ok(mut logger) = syslog::unix(formatter)
+            && logger.notice(&message).is_ok()
+        {
+            return;
+        }
+    }
+
+    eprintln!("audit: notice: {}", message);
+}
@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{self, Path};
+use serde::{Deserialize, Serialize};
+
+use crate::algorithms::{self, hash_file};
+
+/// Database used when `--baseline-db` isn't given.
+pub const DEFAULT_DB: &str = ".hashing-demo-baselines.json";
+
+/// A stored digest, with the algorithm it was taken with so later checks
+/// compare like with like.
+#[derive(Serialize, Deserialize)]
+struct Baseline {
+    algorithm: String,
+    digest: String,
+}
+
+/// Outcome of checking a file against its baseline.
+pub enum BaselineStatus {
+    /// No baseline existed yet; one was recorded.
+    Recorded { digest: String },
+    Unchanged { digest: String },
+    Changed { old: String, new: String },
+    /// The baselined file no longer exists.
+    Deleted { old: String },
+}
+
+/// Compares a file against the baseline stored for its path in the JSON
+/// database at `db_path`, recording a baseline with `algorithm` if none
+/// exists. With `update`, a changed file's new digest replaces the old one
+/// and a deleted file's entry is dropped.
+///
+/// Entries are keyed by absolute path, so the same file checked from
+/// different working directories finds the same baseline.
+pub fn check_baseline(
+    file: &str,
+    db_path: &str,
+    algorithm: &str,
+    update: bool,
+) -> Result<(&'static str, BaselineStatus), Box<dyn std::error::Error>> {
+    let mut db = read_db(db_path)?;
+    let key = path::absolute(file)?.to_string_lossy().into_owned();
+
+    let Some(baseline) = db.get(&key) else {
+        let algorithm = algorithms::lookup(algorithm)?;
+        let digest = hash_file(file, algorithm)?;
+        db.insert(key, Baseline { algorithm: algorithm.to_string(), digest: digest.clone() });
+        write_db(db_path, &db)?;
+        return Ok((algorithm, BaselineStatus::Recorded { digest }));
+    };
+
+    let algorithm = algorithms::lookup(&baseline.algorithm)
+        .map_err(|e| format!("baseline for '{}': {}", file, e))?;
+    let old = baseline.digest.clone();
+
+    if !Path::new(file).exists() {
+        if update {
+            db.remove(&key);
+            write_db(db_path, &db)?;
+        }
+        return Ok((algorithm, BaselineStatus::Deleted { old }));
+    }
+
+    let new = hash_file(file, algorithm)?;
+    if new == old {
+        return Ok((algorithm, BaselineStatus::Unchanged { digest: new }));
+    }
+
+    if update {
+        db.insert(key, Baseline { algorithm: algorithm.to_string(), digest: new.clone() });
+        write_db(db_path, &db)?;
+    }
+    Ok((algorithm, BaselineStatus::Changed { old, new }))
+}
+
+fn read_db(db_path: &str) -> Result<BTreeMap<String, Baseline>, Box<dyn std::error::Error>> {
+    if !Path::new(db_path).exists() {
+        return Ok(BTreeMap::new());
+    }
+
+    let contents = fs::read_to_string(db_path)
+        .map_err(|e| format!("Cannot read baseline database '{}': {}", db_path, e))?;
+    serde_json::from_str(&contents)
+        .map_err(|e| format!("Baseline database '{}' is not valid: {}", db_path, e).into())
+}
+
+fn write_db(db_path: &str, db: &BTreeMap<String, Baseline>) -> Result<(), Box<dyn std::error::Error>> {
+    let contents = serde_json::to_string_pretty(db)?;
+    fs::write(db_path, contents + "\n")
+        .map_err(|e| format!("Cannot write baseline database '{}': {}", db_path, e).into())
+}
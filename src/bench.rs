@@ -0,0 +1,419 @@
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use dialoguer::{Input, Select};
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+use crate::algorithms::{check_file, digest_bytes, hash_file, select_algorithm, StreamHasher, ALGORITHMS};
+use crate::records::read_full;
+use crate::treehash;
+
+/// Buffer sizes tried by the chunk size sweep, 4 KiB to 16 MiB.
+const CHUNK_SIZES: [usize; 7] = [
+    4 * 1024,
+    16 * 1024,
+    64 * 1024,
+    256 * 1024,
+    1024 * 1024,
+    4 * 1024 * 1024,
+    16 * 1024 * 1024,
+];
+
+/// Untimed runs before a jitter measurement, so caches and CPU frequency
+/// have settled before the first timed run.
+const WARMUP_RUNS: usize = 3;
+
+pub fn benchmarks() {
+    let bench_choices = vec![
+        "Algorithm Throughput",
+        "Many Small Files vs One Large File",
+        "Sequential vs Parallel (All Algorithms)",
+        "Timing Jitter (Repeated Runs)",
+        "Read Chunk Size Sweep",
+        "Tree Hash Parallelism",
+    ];
+    let bench_selection = Select::new()
+        .with_prompt("Choose a benchmark")
+        .items(&bench_choices)
+        .default(0)
+        .interact()
+        .unwrap();
+
+    match bench_selection {
+        0 => algorithm_throughput(),
+        1 => small_vs_large_files(),
+        2 => sequential_vs_parallel(),
+        3 => timing_jitter(),
+        4 => chunk_size_sweep(),
+        5 => treehash::tree_hash_interactive(),
+        _ => unreachable!(),
+    }
+}
+
+/// Hashes the same in-memory buffer with every algorithm and charts MB/s
+/// relative to the fastest so far, redrawn as each algorithm finishes. When
+/// stdout isn't a terminal the chart is skipped in favour of plain lines.
+fn algorithm_throughput() {
+    let size_mib: usize = Input::new()
+        .with_prompt("Data size in MiB")
+        .default(16)
+        .interact_text()
+        .unwrap();
+
+    let data = filler_bytes(size_mib * 1024 * 1024);
+    let live = io::stdout().is_terminal();
+    println!("\nThroughput over {} MiB:", size_mib);
+
+    let target = if live { ProgressDrawTarget::stdout() } else { ProgressDrawTarget::hidden() };
+    let chart = MultiProgress::with_draw_target(target);
+    let style = ProgressStyle::with_template("{prefix:<12} {bar:40} {msg}")
+        .unwrap()
+        .progress_chars("█ ");
+
+    let bars: Vec<ProgressBar> = ALGORITHMS
+        .iter()
+        .map(|algorithm| {
+            let bar = chart.add(ProgressBar::new(1000));
+            bar.set_style(style.clone());
+            bar.set_prefix(*algorithm);
+            bar.set_message("waiting");
+            bar
+        })
+        .collect();
+
+    let mut throughputs: Vec<f64> = Vec::new();
+    for (index, algorithm) in ALGORITHMS.iter().enumerate() {
+        bars[index].set_message("hashing...");
+
+        let start = Instant::now();
+        digest_bytes(&data, algorithm).expect("built-in algorithms always hash");
+        let secs = start.elapsed().as_secs_f64().max(f64::EPSILON);
+        throughputs.push(data.len() as f64 / 1_000_000.0 / secs);
+
+        let fastest = throughputs.iter().cloned().fold(f64::MIN, f64::max);
+        for (bar, throughput) in bars.iter().zip(&throughputs) {
+            bar.set_position((throughput / fastest * 1000.0) as u64);
+            bar.set_message(format!("{:.1} MB/s", throughput));
+        }
+
+        if !live {
+            println!("{:<12} {:.1} MB/s", algorithm, throughputs[index]);
+        }
+    }
+
+    for bar in &bars {
+        bar.abandon();
+    }
+    println!();
+}
+
+/// Hashes N small files totaling X bytes and one X-byte file, so the
+/// per-file open/stat/close overhead shows up as the gap between the two.
+fn small_vs_large_files() {
+    let file_count: usize = Input::new()
+        .with_prompt("Number of small files")
+        .default(1000)
+        .interact_text()
+        .unwrap();
+
+    let total_bytes: usize = Input::new()
+        .with_prompt("Total size in bytes")
+        .default(16 * 1024 * 1024)
+        .interact_text()
+        .unwrap();
+
+    if file_count == 0 || total_bytes < file_count {
+        eprintln!("Error: need at least one byte per file");
+        return;
+    }
+
+    let algorithm = select_algorithm();
+
+    let dir = std::env::temp_dir().join(format!("hashing-demo-bench-{}", std::process::id()));
+    let result = run_small_vs_large(&dir, file_count, total_bytes, algorithm);
+    let _ = fs::remove_dir_all(&dir);
+
+    match result {
+        Ok((small, large)) => {
+            println!("\nBenchmark Results:");
+            println!("Algorithm: {}", algorithm);
+            println!("Total size: {} bytes", total_bytes);
+            println!();
+            print_throughput(&format!("{} small files", file_count), file_count, total_bytes, small);
+            print_throughput("1 large file", 1, total_bytes, large);
+            println!();
+
+            let overhead = small.saturating_sub(large).as_secs_f64() / file_count as f64;
+            println!("Per-file overhead (open/stat/close): {:.1} µs", overhead * 1_000_000.0);
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+        }
+    }
+}
+
+fn run_small_vs_large(
+    dir: &Path,
+    file_count: usize,
+    total_bytes: usize,
+    algorithm: &str,
+) -> Result<(Duration, Duration), Box<dyn std::error::Error>> {
+    fs::create_dir_all(dir)?;
+
+    let data = filler_bytes(total_bytes);
+
+    // The last file absorbs the remainder so the totals match exactly.
+    let chunk_size = total_bytes / file_count;
+    let mut small_paths = Vec::with_capacity(file_count);
+    for i in 0..file_count {
+        let start = i * chunk_size;
+        let end = if i + 1 == file_count { total_bytes } else { start + chunk_size };
+        let path = dir.join(format!("small-{:06}.bin", i));
+        fs::write(&path, &data[start..end])?;
+        small_paths.push(path);
+    }
+
+    let large_path = dir.join("large.bin");
+    fs::write(&large_path, &data)?;
+
+    let small = time_hashes(&small_paths, algorithm)?;
+    let large = time_hashes(&[large_path], algorithm)?;
+
+    Ok((small, large))
+}
+
+fn time_hashes(paths: &[PathBuf], algorithm: &str) -> Result<Duration, Box<dyn std::error::Error>> {
+    let start = Instant::now();
+    for path in paths {
+        hash_file(&path.to_string_lossy(), algorithm)?;
+    }
+    Ok(start.elapsed())
+}
+
+fn print_throughput(label: &str, file_count: usize, total_bytes: usize, elapsed: Duration) {
+    let secs = elapsed.as_secs_f64().max(f64::EPSILON);
+    let files_per_sec = file_count as f64 / secs;
+    let mb_per_sec = total_bytes as f64 / 1_000_000.0 / secs;
+
+    println!("{}:", label);
+    println!("  Time:       {:.3} s", secs);
+    println!("  Files/sec:  {:.1}", files_per_sec);
+    println!("  Throughput: {:.2} MB/s", mb_per_sec);
+}
+
+/// Hashes one file with every algorithm, first one after another and then
+/// with one thread per algorithm, over the same in-memory buffer so only the
+/// hashing itself is timed.
+fn sequential_vs_parallel() {
+    let file_path: String = Input::new()
+        .with_prompt("Enter file path to hash")
+        .interact_text()
+        .unwrap();
+
+    let data = match fs::read(&file_path) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return;
+        }
+    };
+
+    let start = Instant::now();
+    let sequential: Vec<Vec<u8>> = ALGORITHMS
+        .iter()
+        .map(|algorithm| digest_bytes(&data, algorithm).expect("built-in algorithms always hash"))
+        .collect();
+    let sequential_time = start.elapsed();
+
+    let start = Instant::now();
+    let parallel: Vec<Vec<u8>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = ALGORITHMS
+            .iter()
+            .map(|algorithm| {
+                let data = &data;
+                scope.spawn(move || digest_bytes(data, algorithm).expect("built-in algorithms always hash"))
+            })
+            .collect();
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+    let parallel_time = start.elapsed();
+
+    if sequential != parallel {
+        eprintln!("Error: parallel hashing produced different digests");
+        return;
+    }
+
+    let sequential_secs = sequential_time.as_secs_f64().max(f64::EPSILON);
+    let parallel_secs = parallel_time.as_secs_f64().max(f64::EPSILON);
+
+    println!("\nBenchmark Results:");
+    println!("File: '{}' ({} bytes)", file_path, data.len());
+    println!("Algorithms: {}", ALGORITHMS.join(", "));
+    println!();
+    println!("Sequential: {:.3} s", sequential_secs);
+    println!("Parallel:   {:.3} s ({} threads)", parallel_secs, ALGORITHMS.len());
+    println!("Speedup:    {:.2}x", sequential_secs / parallel_secs);
+    println!();
+    println!("The parallel run can be no faster than its slowest algorithm, and for small");
+    println!("files thread start-up costs more than it saves.\n");
+}
+
+/// Hashes the same buffer K times and reports the spread of the individual
+/// timings, since a single run can land on a context switch or a cold cache
+/// and make an algorithm look faster or slower than it is.
+fn timing_jitter() {
+    let size_kib: usize = Input::new()
+        .with_prompt("Data size in KiB")
+        .default(1024)
+        .interact_text()
+        .unwrap();
+
+    let runs: usize = Input::new()
+        .with_prompt("Number of timed runs (K)")
+        .default(100)
+        .validate_with(|runs: &usize| if *runs >= 2 { Ok(()) } else { Err("need at least 2 runs") })
+        .interact_text()
+        .unwrap();
+
+    let algorithm = select_algorithm();
+    let data = filler_bytes(size_kib * 1024);
+
+    let timings: Result<Vec<f64>, String> = (0..WARMUP_RUNS)
+        .try_for_each(|_| digest_bytes(&data, algorithm).map(|_| ()))
+        .and_then(|_| {
+            (0..runs)
+                .map(|_| {
+                    let start = Instant::now();
+                    digest_bytes(&data, algorithm).map(|_| start.elapsed().as_secs_f64() * 1_000_000.0)
+                })
+                .collect()
+        });
+    let mut timings = match timings {
+        Ok(timings) => timings,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return;
+        }
+    };
+    timings.sort_by(f64::total_cmp);
+
+    let median = if runs.is_multiple_of(2) {
+        (timings[runs / 2 - 1] + timings[runs / 2]) / 2.0
+    } else {
+        timings[runs / 2]
+    };
+    let mean = timings.iter().sum::<f64>() / runs as f64;
+    let variance = timings.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / (runs - 1) as f64;
+    let stddev = variance.sqrt();
+
+    println!("\nTiming Jitter:");
+    println!("Algorithm: {}", algorithm);
+    println!("Data size: {} KiB, {} timed runs after {} warm-up runs", size_kib, runs, WARMUP_RUNS);
+    println!();
+    println!("  Min:    {:>12.1} µs", timings[0]);
+    println!("  Median: {:>12.1} µs", median);
+    println!("  Max:    {:>12.1} µs", timings[runs - 1]);
+    println!("  Mean:   {:>12.1} µs", mean);
+    println!("  Stddev: {:>12.1} µs ({:.1}% of mean)", stddev, stddev / mean.max(f64::EPSILON) * 100.0);
+    println!();
+    println!("The minimum is the best estimate of the algorithm's own cost; the spread above");
+    println!("it is noise from the rest of the system.\n");
+}
+
+/// Hashes a file read through buffers from 4 KiB to 16 MiB and ranks the
+/// sizes by throughput. Small buffers pay a system call per chunk; large ones
+/// stop fitting in the CPU caches, so the best size depends on the machine
+/// and the storage. One untimed pass first pulls the file into the OS page
+/// cache, so every size is measured on the same footing: this times cached
+/// reads plus hashing, not the disk.
+fn chunk_size_sweep() {
+    let file_path: String = Input::new()
+        .with_prompt("Enter file path to hash")
+        .interact_text()
+        .unwrap();
+    let algorithm = select_algorithm();
+
+    if let Err(e) = check_file(&file_path).and_then(|()| hash_file_chunked(&file_path, algorithm, 1024 * 1024).map(|_| ())) {
+        eprintln!("Error: {}", e);
+        return;
+    }
+
+    let mut results = Vec::new();
+    let mut digests = Vec::new();
+    for chunk_size in CHUNK_SIZES {
+        let start = Instant::now();
+        match hash_file_chunked(&file_path, algorithm, chunk_size) {
+            Ok((digest, bytes)) => {
+                let secs = start.elapsed().as_secs_f64().max(f64::EPSILON);
+                results.push((chunk_size, bytes as f64 / 1_000_000.0 / secs));
+                digests.push(digest);
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return;
+            }
+        }
+    }
+    if digests.windows(2).any(|pair| pair[0] != pair[1]) {
+        eprintln!("Error: chunk size changed the digest");
+        return;
+    }
+
+    results.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let fastest = results[0].1;
+
+    println!("\nChunk Size Sweep:");
+    println!("File: '{}'", file_path);
+    println!("Algorithm: {}", algorithm);
+    println!();
+    println!("  {:>10}  {:>12}  {:>9}", "Chunk", "MB/s", "vs best");
+    for (index, (chunk_size, mb_per_sec)) in results.iter().enumerate() {
+        let marker = if index == 0 { "  <- fastest" } else { "" };
+        println!("  {:>10}  {:>12.2}  {:>8.0}%{}", format_size(*chunk_size), mb_per_sec, mb_per_sec / fastest * 100.0, marker);
+    }
+    println!();
+    println!("The file was read once before timing, so these are page-cache reads: a cold");
+    println!("read from disk or the network will be slower and may favor larger chunks.");
+    println!("Differences of a few percent are within run-to-run noise.\n");
+}
+
+/// Hashes a file through a `chunk_size` buffer, returning the digest and the
+/// number of bytes read.
+fn hash_file_chunked(file_path: &str, algorithm: &str, chunk_size: usize) -> Result<(Vec<u8>, u64), Box<dyn std::error::Error>> {
+    let mut file = fs::File::open(file_path)?;
+    let mut buffer = vec![0u8; chunk_size];
+    let mut hasher = StreamHasher::new(algorithm);
+    let mut total = 0u64;
+    loop {
+        let read = read_full(&mut file, &mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        total += read as u64;
+    }
+    Ok((hasher.finalize()?, total))
+}
+
+fn format_size(bytes: usize) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{} MiB", bytes / (1024 * 1024))
+    } else {
+        format!("{} KiB", bytes / 1024)
+    }
+}
+
+/// Deterministic non-repeating filler so no file is trivially compressible
+/// or identical to another.
+fn filler_bytes(len: usize) -> Vec<u8> {
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect()
+}
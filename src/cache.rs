@@ -0,0 +1,93 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{self, Path};
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+use serde::{Deserialize, Serialize};
+
+use crate::algorithms::hash_file;
+
+/// Cache file used when `--cache` is given without a path.
+pub const DEFAULT_CACHE: &str = ".hashing-demo-cache.json";
+
+/// Digests of files as they were when last hashed, keyed by absolute path.
+///
+/// An entry is reused only while the file's size and modification time are
+/// unchanged, which makes re-verifying a large, mostly static tree cheap. It
+/// trusts mtime, though: an edit that keeps the size and restores the old
+/// mtime (`touch -r`, `cp -p`, some sync tools, or a write within the file
+/// system's timestamp granularity) is served the stale digest. Leave the cache
+/// off when that matters.
+#[derive(Default, Serialize, Deserialize)]
+pub struct HashCache {
+    entries: BTreeMap<String, CacheEntry>,
+    #[serde(skip)]
+    pub hits: usize,
+    #[serde(skip)]
+    pub misses: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    size: u64,
+    mtime_ns: u128,
+    algorithm: String,
+    digest: String,
+}
+
+impl HashCache {
+    /// Loads the cache at `path`, or an empty one if the file doesn't exist.
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        if !Path::new(path).exists() {
+            return Ok(HashCache::default());
+        }
+
+        let contents = fs::read_to_string(path).map_err(|e| format!("Cannot read cache '{}': {}", path, e))?;
+        serde_json::from_str(&contents).map_err(|e| format!("Cache '{}' is not valid; clear it with --clear-cache: {}", path, e).into())
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let contents = serde_json::to_string(self)?;
+        fs::write(path, contents + "\n").map_err(|e| format!("Cannot write cache '{}': {}", path, e).into())
+    }
+}
+
+/// Deletes the cache file, returning whether there was one.
+pub fn clear(path: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(format!("Cannot remove cache '{}': {}", path, e).into()),
+    }
+}
+
+/// `hash_file` through the cache: a stored digest is returned if the file's
+/// size and mtime still match, otherwise the file is hashed and the entry
+/// replaced. The lock is not held while hashing, so worker threads can share
+/// one cache.
+pub fn hash_file_cached(cache: &Mutex<HashCache>, file: &str, algorithm: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let key = path::absolute(file)?.to_string_lossy().into_owned();
+
+    // Taken before hashing, so a write that lands mid-hash changes the mtime
+    // and the next run hashes the file again.
+    let metadata = fs::metadata(file).map_err(|e| format!("Cannot stat '{}': {}", file, e))?;
+    let size = metadata.len();
+    let mtime_ns = metadata.modified()?.duration_since(UNIX_EPOCH).map(|mtime| mtime.as_nanos()).unwrap_or(0);
+
+    {
+        let mut cache = cache.lock().unwrap();
+        let cached = cache.entries.get(&key).and_then(|entry| {
+            (entry.size == size && entry.mtime_ns == mtime_ns && entry.algorithm == algorithm).then(|| entry.digest.clone())
+        });
+        if let Some(digest) = cached {
+            cache.hits += 1;
+            return Ok(digest);
+        }
+    }
+
+    let digest = hash_file(file, algorithm)?;
+    let mut cache = cache.lock().unwrap();
+    cache.misses += 1;
+    cache.entries.insert(key, CacheEntry { size, mtime_ns, algorithm: algorithm.to_string(), digest: digest.clone() });
+    Ok(digest)
+}
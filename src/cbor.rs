@@ -0,0 +1,53 @@
+use ciborium::Value;
+
+/// Re-encodes a single CBOR data item in the core deterministic encoding of
+/// RFC 8949 section 4.2.1, so that any two encodings of the same data give
+/// the same bytes:
+/// - integers, lengths and tags use their shortest form, and bignums that fit
+///   in 64 bits become plain integers;
+/// - floats use the shortest of half, single or double precision that keeps
+///   the value exactly;
+/// - indefinite-length strings, arrays and maps become definite-length;
+/// - map entries are sorted by the bytewise order of their encoded keys.
+///
+/// Fails on malformed or truncated input, trailing bytes after the item, and
+/// maps with duplicate keys, which have no canonical form.
+pub fn canonical_cbor(input: &[u8]) -> Result<Vec<u8>, String> {
+    let mut reader = input;
+    let value: Value = ciborium::from_reader(&mut reader).map_err(|e| format!("malformed CBOR: {}", e))?;
+    if !reader.is_empty() {
+        return Err(format!(
+            "trailing data: {} bytes after the first CBOR item, which ends at offset {}",
+            reader.len(),
+            input.len() - reader.len()
+        ));
+    }
+
+    encode_value(&canonicalize(value)?)
+}
+
+fn canonicalize(value: Value) -> Result<Value, String> {
+    Ok(match value {
+        Value::Array(items) => Value::Array(items.into_iter().map(canonicalize).collect::<Result<_, _>>()?),
+        Value::Tag(tag, inner) => Value::Tag(tag, Box::new(canonicalize(*inner)?)),
+        Value::Map(entries) => {
+            let mut encoded = Vec::with_capacity(entries.len());
+            for (key, value) in entries {
+                let key = canonicalize(key)?;
+                encoded.push((encode_value(&key)?, key, canonicalize(value)?));
+            }
+            encoded.sort_by(|a, b| a.0.cmp(&b.0));
+            if let Some(pair) = encoded.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+                return Err(format!("duplicate map key 0x{}", hex::encode(&pair[0].0)));
+            }
+            Value::Map(encoded.into_iter().map(|(_, key, value)| (key, value)).collect())
+        }
+        other => other,
+    })
+}
+
+fn encode_value(value: &Value) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    ciborium::into_writer(value, &mut bytes).map_err(|e| format!("cannot encode CBOR: {}", e))?;
+    Ok(bytes)
+}
@@ -0,0 +1,976 @@
+use std::fs;
+use std::io::{self, Read, Write};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use clap::{ArgGroup, Parser};
+use uuid::Uuid;
+
+use crate::algorithms::{self, read_file, AlgorithmSpec};
+use crate::audit;
+use crate::baseline::{self, BaselineStatus};
+use crate::cache::{self, HashCache};
+use crate::dataset;
+use crate::fixtures;
+use crate::follow;
+use crate::fuzz;
+use crate::journal::{self, Start};
+use crate::jsondiff;
+use crate::metrics::{self, HashSample};
+use crate::output::{self, format_abbrev, format_coreutils, OutputPipeline, UuidStyle};
+#[cfg(feature = "plugins")]
+use crate::plugin;
+use crate::priority;
+#[cfg(all(target_os = "linux", feature = "procmem"))]
+use crate::procmem;
+use crate::records;
+#[cfg(feature = "remote")]
+use crate::remote;
+use crate::salt;
+use crate::scrub;
+use crate::source::{self, CommentStyles};
+#[cfg(feature = "serve")]
+use crate::serve;
+#[cfg(target_os = "linux")]
+use crate::sparse;
+use crate::transform::{self, Transform};
+use crate::treehash::{self, TreeKind};
+use crate::uuidv5;
+use crate::vectors::{self, VectorFormat};
+use crate::verify;
+
+/// Hashing Function Demo. Run without arguments for the interactive menu.
+#[derive(Parser)]
+#[command(version)]
+// The modes each modifier flag applies to. A modifier requires one of them,
+// so passing it alone is an error instead of opening the interactive menu,
+// which would silently ignore it.
+#[command(group(ArgGroup::new("cached_input").args(["file", "verify_dir", "clear_cache"]).multiple(true)))]
+#[command(group(ArgGroup::new("seeded_input").args(["text", "file", "text_file", "records", "follow", "tee", "compare_json"]).multiple(true)))]
+#[command(group(ArgGroup::new("printed_input").args(["text", "file", "text_file", "records", "follow", "tee"]).multiple(true)))]
+#[command(group(ArgGroup::new("dumped_input").args(["text", "file", "text_file", "records"]).multiple(true)))]
+#[command(group(ArgGroup::new("metered_input").args(["text", "file", "text_file", "records", "verify_dir"]).multiple(true)))]
+pub struct Cli {
+    /// Hashing algorithm to use (case-insensitive). Blake2b alone takes a
+    /// parameter, its output length in bytes as NAME:LENGTH, e.g.
+    /// `blake2b:32`. Defaults to $HASHING_DEMO_ALGO, then SHA-256
+    #[arg(long)]
+    pub algo: Option<String>,
+
+    /// Seed for the non-cryptographic hashes, in decimal or 0x-prefixed hex,
+    /// to reproduce seeded values from hash tables and other systems.
+    /// FarmHash64 takes a 64-bit seed and becomes FarmHash's Hash64WithSeed,
+    /// which differs from the unseeded digest even for seed 0. CRC32 takes a
+    /// 32-bit initial CRC as zlib's crc32() does: seeding with the CRC of A
+    /// and hashing B gives the CRC of A then B, and seed 0 is plain CRC32
+    #[arg(long, value_name = "SEED", requires = "seeded_input", value_parser = algorithms::parse_seed)]
+    pub seed: Option<u64>,
+
+    /// Text to hash; repeat to hash several strings in order
+    #[arg(long, conflicts_with = "file")]
+    pub text: Vec<String>,
+
+    /// Read each --text as TEXT:TAG, splitting at the last colon, and print
+    /// the results grouped by tag, tags in sorted order and inputs in the
+    /// order given. Texts without a colon are listed last, untagged
+    #[arg(long, requires = "text")]
+    pub tagged: bool,
+
+    /// File to hash
+    #[arg(long)]
+    pub file: Option<String>,
+
+    /// With --file, skip the holes of a sparse file via SEEK_DATA/SEEK_HOLE
+    /// instead of reading them (Linux); the digest is unchanged
+    #[cfg(target_os = "linux")]
+    #[arg(long, requires = "file", conflicts_with_all = ["cache", "throttle", "journal", "salted", "hexdump", "normalize_source", "case_fold", "xor_key", "compact_size_prefix"])]
+    pub sparse: bool,
+
+    /// With --file, checkpoint the hasher state and byte offset to PATH as
+    /// the hash runs, and resume from the checkpoint if the hash was cut
+    /// short. Only SHA-256 and CRC32 state can be journaled. The journal is
+    /// removed when the hash completes
+    #[arg(long, value_name = "PATH", requires = "file", conflicts_with_all = ["cache", "throttle", "normalize_source", "case_fold", "xor_key", "compact_size_prefix"])]
+    pub journal: Option<String>,
+
+    /// Megabytes (10^6 bytes) hashed between --journal checkpoints
+    #[arg(long, value_name = "MB", default_value_t = 64, requires = "journal", value_parser = clap::value_parser!(u64).range(1..))]
+    pub journal_interval: u64,
+
+    /// Read the text to hash from PATH, or from stdin when PATH is `-`,
+    /// keeping every byte including newlines. Unlike --file this accepts
+    /// pipes, so multi-line text can come from a heredoc
+    /// (`--text-file - <<'EOF'`) or, when stdin is busy, from process
+    /// substitution (`--text-file <(printf 'a\nb')`). Note that a heredoc
+    /// always ends with a newline.
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["text", "file"])]
+    pub text_file: Option<String>,
+
+    /// Hash FILE as it grows, like `tail -f`, printing a digest of everything
+    /// so far whenever new data arrives and the final digest on Ctrl-C. A
+    /// truncated or rotated file starts the hash over
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["text", "file", "text_file", "normalize_source", "case_fold", "xor_key", "compact_size_prefix"])]
+    pub follow: Option<String>,
+
+    /// Seconds between checks for new data in --follow
+    #[arg(long, value_name = "SECS", default_value_t = 1.0, requires = "follow", value_parser = parse_interval)]
+    pub follow_interval: f64,
+
+    /// Read length-prefixed records from stdin (4-byte big-endian length,
+    /// then that many bytes) and print one digest per record
+    #[arg(long, conflicts_with_all = ["text", "file", "text_file"])]
+    pub records: bool,
+
+    /// Copy stdin to stdout unchanged and print its digest to stderr at the
+    /// end, for hashing data as it flows through a pipe
+    #[arg(long, conflicts_with_all = ["text", "file", "text_file", "records", "normalize_source", "case_fold", "xor_key", "compact_size_prefix"])]
+    pub tee: bool,
+
+    /// Hash a region of another process's memory from /proc/PID/mem
+    /// (Linux, `procmem` feature); needs --range
+    #[cfg(all(target_os = "linux", feature = "procmem"))]
+    #[arg(long, value_name = "PID", requires = "range")]
+    pub proc_mem: Option<u32>,
+
+    /// Address range for --proc-mem as hex START-END, as in /proc/PID/maps
+    #[cfg(all(target_os = "linux", feature = "procmem"))]
+    #[arg(long, value_name = "START-END", requires = "proc_mem")]
+    pub range: Option<String>,
+
+    /// With --file, download the checksum file at URL (e.g. a release's
+    /// SHA256SUMS), find the line for the file's name and verify the file
+    /// against it (`remote` feature); exits nonzero on a mismatch
+    #[cfg(feature = "remote")]
+    #[arg(long, value_name = "URL", requires = "file", conflicts_with_all = ["cache", "throttle", "journal", "normalize_source", "case_fold", "xor_key", "compact_size_prefix"])]
+    pub check_remote: Option<String>,
+
+    /// Hash an NDJSON export as a set of records: each record canonicalized,
+    /// sorted by --sort-key, then hashed, so dumps of the same rows match
+    /// whatever their order. Exports too large for memory are sorted on disk
+    #[arg(long, value_name = "PATH", requires = "sort_key", conflicts_with_all = ["text", "file", "text_file"])]
+    pub ndjson: Option<String>,
+
+    /// Field to sort --ndjson records by, or a JSON Pointer like `/meta/id`
+    #[arg(long, value_name = "KEY", requires = "ndjson")]
+    pub sort_key: Option<String>,
+
+    /// Compare two JSON documents by the digests of their canonical forms,
+    /// so formatting and key order don't count, and list the paths (as JSON
+    /// Pointers) that were added, removed or changed; exits nonzero if they
+    /// differ
+    #[arg(long, num_args = 2, value_names = ["FIRST", "SECOND"], conflicts_with_all = ["text", "file", "text_file"])]
+    pub compare_json: Vec<String>,
+
+    /// Hash FILE as a tree (see --tree-kind) on one thread and on --threads
+    /// threads, check both give the same root and report the speedup, with
+    /// sequential SHA-256 for contrast
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["algo", "text", "file", "text_file", "normalize_source", "case_fold", "xor_key", "compact_size_prefix"])]
+    pub tree_hash: Option<String>,
+
+    /// Tree for --tree-hash
+    #[arg(long, value_enum, value_name = "KIND", default_value = "blake3", requires = "tree_hash")]
+    pub tree_kind: TreeKind,
+
+    /// Most threads --tree-hash spreads leaves over; defaults to one per core
+    #[arg(long, value_name = "N", requires = "tree_hash", value_parser = clap::value_parser!(u32).range(1..=1024))]
+    pub threads: Option<u32>,
+
+    /// Check FILE against its stored baseline digest, recording one on first use
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["text", "file", "text_file"])]
+    pub baseline: Option<String>,
+
+    /// JSON database of baselines, keyed by absolute path
+    #[arg(long, value_name = "PATH", default_value = baseline::DEFAULT_DB, requires = "baseline")]
+    pub baseline_db: String,
+
+    /// Replace the stored baseline when the file has changed or been deleted
+    #[arg(long, requires = "baseline")]
+    pub update_baseline: bool,
+
+    /// Hash the one --text, --file or --text-file input prefixed with a
+    /// random salt kept for RECORD in --salt-db; later runs for RECORD reuse
+    /// the salt, so its digests stay comparable
+    #[arg(long, value_name = "RECORD", conflicts_with_all = ["tagged", "journal", "cache", "normalize_source", "case_fold", "xor_key", "compact_size_prefix"])]
+    pub salted: Option<String>,
+
+    /// JSON database of salts and digests for --salted, keyed by record
+    #[arg(long, value_name = "PATH", default_value = salt::DEFAULT_DB, requires = "salted")]
+    pub salt_db: String,
+
+    /// Replace the record's stored salt with a fresh one
+    #[arg(long, requires = "salted")]
+    pub new_salt: bool,
+
+    /// Re-hash every file under DIR against a database of digests and report
+    /// any whose contents changed while size and mtime did not (likely
+    /// bit-rot); exits nonzero if any did. Meant to be run periodically
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["text", "file", "text_file", "baseline"])]
+    pub scrub: Option<String>,
+
+    /// JSON database for --scrub, keyed by absolute path
+    #[arg(long, value_name = "PATH", default_value = scrub::DEFAULT_DB, requires = "scrub")]
+    pub scrub_db: String,
+
+    /// Serve the digests of every file under DIR as a JSON map at
+    /// `/manifest.json` over plain, unauthenticated HTTP (`serve` feature);
+    /// for trusted networks only
+    #[cfg(feature = "serve")]
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["text", "file", "text_file", "baseline", "scrub"])]
+    pub serve: Option<String>,
+
+    /// Address and port for --serve
+    #[cfg(feature = "serve")]
+    #[arg(long, value_name = "ADDR:PORT", default_value = serve::DEFAULT_LISTEN, requires = "serve")]
+    pub listen: String,
+
+    /// Re-hash DIR against --manifest and report OK/changed/missing/new
+    /// files; exits nonzero if anything differs
+    #[arg(long, value_name = "DIR", requires = "manifest")]
+    pub verify_dir: Option<String>,
+
+    /// Manifest file for --verify-dir
+    #[arg(long, value_name = "PATH", requires = "verify_dir")]
+    pub manifest: Option<String>,
+
+    /// Print the name-based UUIDv5 of NAME in --namespace: SHA-1 of the
+    /// namespace and name, cut to a UUID
+    #[arg(long, value_name = "NAME", requires = "namespace", conflicts_with = "algo")]
+    pub uuid5: Option<String>,
+
+    /// Namespace for --uuid5: `dns`, `url`, `oid`, `x500` or a UUID
+    #[arg(long, value_name = "NAMESPACE", requires = "uuid5", value_parser = uuidv5::parse_namespace)]
+    pub namespace: Option<Uuid>,
+
+    /// Print a table of every algorithm's digest of the standard test
+    /// inputs (empty, "abc", a two-block message, a million "a"s)
+    #[arg(long, value_name = "FORMAT", num_args = 0..=1, default_missing_value = "markdown")]
+    pub gen_vectors: Option<VectorFormat>,
+
+    /// Recompute each `{input, algorithm, expected_digest}` fixture in a JSON
+    /// file and report mismatches; exits nonzero if any fail
+    #[arg(long, value_name = "PATH")]
+    pub check_fixtures: Option<String>,
+
+    /// Hash N random input pairs and check the comparison logic's
+    /// difference counting for internal consistency
+    #[arg(long, value_name = "N")]
+    pub fuzz_compare: Option<u64>,
+
+    /// Seed for --fuzz-compare, to reproduce a previous run
+    #[arg(long, value_name = "SEED", requires = "fuzz_compare")]
+    pub fuzz_seed: Option<u64>,
+
+    /// Strip comments in the listed styles (`slash` for //, `hash` for #,
+    /// `block` for /* */, comma-separated, or `none`) and insignificant
+    /// whitespace before hashing, so reformatted source hashes the same. A
+    /// heuristic, not a parser: see the transform's notes. Applied first
+    #[arg(long, value_name = "STYLES", value_parser = source::parse_comment_styles)]
+    pub normalize_source: Option<CommentStyles>,
+
+    /// Lowercase the input before hashing so text differing only in case
+    /// matches (lossy; applied after --normalize-source, before the rest)
+    #[arg(long)]
+    pub case_fold: bool,
+
+    /// XOR the input with this repeating hex key before hashing
+    #[arg(long, value_name = "HEX")]
+    pub xor_key: Option<String>,
+
+    /// Prepend the input's length as a Bitcoin CompactSize varint before
+    /// hashing (after --xor-key, if both are given)
+    #[arg(long)]
+    pub compact_size_prefix: bool,
+
+    /// Reuse digests of files whose size and mtime are unchanged, stored in
+    /// PATH (`.hashing-demo-cache.json` when omitted); applies to --file and
+    /// --verify-dir, and not with transforms, which change what is hashed.
+    /// Edits that preserve mtime go unnoticed
+    #[arg(long, value_name = "PATH", num_args = 0..=1, default_missing_value = cache::DEFAULT_CACHE, requires = "cached_input", conflicts_with_all = ["normalize_source", "case_fold", "xor_key", "compact_size_prefix"])]
+    pub cache: Option<String>,
+
+    /// Delete the hash cache (the --cache path, or the default) and exit
+    #[arg(long)]
+    pub clear_cache: bool,
+
+    /// Abbreviate digests to their first N hex digits, like Git's short
+    /// hashes (7 when N is omitted); truncation weakens collision resistance
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "7", requires = "printed_input")]
+    pub abbrev: Option<usize>,
+
+    /// Print integer-valued digests (FarmHash64 and CRC32) least significant
+    /// byte first, matching tools that dump the value's memory
+    #[arg(long, requires = "printed_input")]
+    pub little_endian: bool,
+
+    /// Post-process each digest through a comma-separated pipeline of steps
+    /// applied in order: `truncate:N` (keep N bytes) and `reverse` on the raw
+    /// bytes, then `hex` or `base64`, then `uppercase` (hex only), e.g.
+    /// `truncate:8,base64` or `reverse,hex`. Hex is used if no encoding is
+    /// given
+    #[arg(long, value_name = "STEPS", requires = "printed_input", conflicts_with_all = ["abbrev", "little_endian"], value_parser = output::parse_pipeline)]
+    pub out: Option<OutputPipeline>,
+
+    /// After each digest, print a `hexdump -C` view of the exact bytes
+    /// hashed, after any transforms, showing at most N bytes (512 when N is
+    /// omitted), to find stray newlines, BOMs and encoding surprises
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "512", requires = "dumped_input", conflicts_with_all = ["quiet", "journal", "cache", "salted"])]
+    pub hexdump: Option<usize>,
+
+    /// Print each digest's first 16 bytes as a UUID, as a UUIDv8 (`v8`, the
+    /// default) or unmodified (`raw`); an identifier, not a secure digest,
+    /// and needs a digest of at least 128 bits
+    #[arg(long, value_enum, value_name = "STYLE", num_args = 0..=1, default_missing_value = "v8", requires = "printed_input", conflicts_with_all = ["abbrev", "little_endian", "out"])]
+    pub uuid: Option<UuidStyle>,
+
+    /// Write Prometheus text-format metrics for the run to PATH (replaced
+    /// atomically, for the node_exporter textfile collector), or to stdout
+    /// in place of the usual output when PATH is omitted. Hashing reports
+    /// `hash_duration_seconds`, `hash_bytes` and `hash_info` (digest as a
+    /// label) per input, labeled `algo` and `input`; --verify-dir reports
+    /// `verify_duration_seconds`, `verify_files_{ok,changed,missing,new,unreadable}`
+    /// and `verify_success`, labeled `dir` and `manifest`
+    #[arg(long, value_name = "PATH", num_args = 0..=1, default_missing_value = metrics::STDOUT, requires = "metered_input")]
+    pub prometheus: Option<String>,
+
+    /// Run at nice value N (0-19, higher is lower priority) so a long hash
+    /// doesn't slow the rest of the system; Unix only, ignored elsewhere
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(i32).range(0..=19))]
+    pub nice: Option<i32>,
+
+    /// With --file, read no faster than this many MB (10^6 bytes) per second
+    #[arg(long, value_name = "MB_PER_SEC", requires = "file", conflicts_with = "cache", value_parser = parse_throttle)]
+    pub throttle: Option<f64>,
+
+    /// Retry a read that fails transiently (interrupted, would block, timed
+    /// out) up to N times, waiting 100 ms and doubling each time; other
+    /// errors fail at once. For flaky network file systems
+    #[arg(long, value_name = "N", default_value_t = algorithms::DEFAULT_READ_RETRIES)]
+    pub retries: u32,
+
+    /// Print only the digests, one per line
+    #[arg(long, short)]
+    pub quiet: bool,
+
+    /// Also record each computed hash as an audit event in syslog (stderr
+    /// when syslog is unavailable or the `syslog` feature is off)
+    #[arg(long)]
+    pub syslog: bool,
+
+    /// Comma-separated algorithms to offer in the interactive menus; the rest
+    /// stay hidden there but remain available through `--algo`
+    #[arg(long, value_name = "NAMES", value_delimiter = ',')]
+    pub menu_algos: Vec<String>,
+
+    /// Plugin config file listing external hash commands (`plugins`
+    /// feature). Defaults to ~/.hashing-demo-plugins.json; never read from
+    /// the working directory
+    #[cfg(feature = "plugins")]
+    #[arg(long, value_name = "PATH")]
+    pub plugins: Option<String>,
+
+    /// Register an external command as the algorithm NAME (`plugins`
+    /// feature). The command gets the input on stdin and prints the digest in
+    /// hex as its first word on stdout, e.g. `b3sum --no-names`; it is run
+    /// once on the empty input to check it works. Plugins are not sandboxed:
+    /// the command runs through the shell as you, sees every byte hashed with
+    /// it, and the config file is as trusted as a script on your PATH
+    #[cfg(feature = "plugins")]
+    #[arg(long, value_name = "NAME", requires = "plugin_command")]
+    pub register_plugin: Option<String>,
+
+    /// Shell command for --register-plugin; its first word must be a program
+    /// that exists
+    #[cfg(feature = "plugins")]
+    #[arg(long, value_name = "COMMAND", requires = "register_plugin")]
+    pub plugin_command: Option<String>,
+
+    /// Remove the plugin NAME from the config file (`plugins` feature)
+    #[cfg(feature = "plugins")]
+    #[arg(long, value_name = "NAME", conflicts_with = "register_plugin")]
+    pub remove_plugin: Option<String>,
+}
+
+impl Cli {
+    pub fn is_non_interactive(&self) -> bool {
+        #[cfg(all(target_os = "linux", feature = "procmem"))]
+        if self.proc_mem.is_some() {
+            return true;
+        }
+        #[cfg(feature = "serve")]
+        if self.serve.is_some() {
+            return true;
+        }
+        #[cfg(feature = "plugins")]
+        if self.register_plugin.is_some() || self.remove_plugin.is_some() {
+            return true;
+        }
+
+        self.algo.is_some()
+            || !self.text.is_empty()
+            || self.file.is_some()
+            || self.text_file.is_some()
+            || self.follow.is_some()
+            || self.records
+            || self.tee
+            || self.ndjson.is_some()
+            || !self.compare_json.is_empty()
+            || self.tree_hash.is_some()
+            || self.baseline.is_some()
+            || self.salted.is_some()
+            || self.scrub.is_some()
+            || self.clear_cache
+            || self.verify_dir.is_some()
+            || self.uuid5.is_some()
+            || self.gen_vectors.is_some()
+            || self.check_fixtures.is_some()
+            || self.fuzz_compare.is_some()
+            || self.normalize_source.is_some()
+            || self.case_fold
+            || self.xor_key.is_some()
+            || self.compact_size_prefix
+    }
+}
+
+pub fn run(args: &Cli) -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "plugins")]
+    if args.register_plugin.is_some() || args.remove_plugin.is_some() {
+        let config = plugin::config_path(args.plugins.as_deref()).ok_or("$HOME is not set; name the plugin config with --plugins")?;
+        if let (Some(name), Some(command)) = (&args.register_plugin, &args.plugin_command) {
+            let empty = plugin::register(&config, name, command)?;
+            println!("Registered plugin '{}' in '{}'", name, config.display());
+            println!("{}  (empty input)", empty);
+        }
+        if let Some(name) = &args.remove_plugin {
+            if !plugin::unregister(&config, name)? {
+                return Err(format!("no plugin '{}' in '{}'", name, config.display()).into());
+            }
+            println!("Removed plugin '{}' from '{}'", name, config.display());
+        }
+        return Ok(());
+    }
+
+    let spec = match &args.algo {
+        Some(spec) => AlgorithmSpec::parse(spec)?,
+        None => AlgorithmSpec::plain(algorithms::default_algorithm()),
+    };
+    let spec = match args.seed {
+        Some(seed) => spec.with_seed(seed)?,
+        None => spec,
+    };
+    let algorithm = spec.algorithm;
+
+    // Parameters only reach the modes that hash through the spec directly.
+    #[allow(unused_mut)]
+    let mut hashes_by_name = args.ndjson.is_some() || args.baseline.is_some() || args.scrub.is_some() || args.verify_dir.is_some() || args.fuzz_compare.is_some() || args.cache.is_some() || args.journal.is_some();
+    #[cfg(target_os = "linux")]
+    {
+        hashes_by_name |= args.sparse;
+    }
+    #[cfg(all(target_os = "linux", feature = "procmem"))]
+    {
+        hashes_by_name |= args.proc_mem.is_some();
+    }
+    #[cfg(feature = "serve")]
+    {
+        hashes_by_name |= args.serve.is_some();
+    }
+    #[cfg(feature = "remote")]
+    {
+        hashes_by_name |= args.check_remote.is_some();
+    }
+    if !spec.is_plain() && hashes_by_name {
+        return Err(format!("'{}': algorithm parameters only apply to --text, --file, --text-file, --records, --tee, --follow, --salted and --compare-json", spec).into());
+    }
+
+    if args.clear_cache {
+        let path = args.cache.as_deref().unwrap_or(cache::DEFAULT_CACHE);
+        if cache::clear(path)? {
+            println!("Removed cache '{}'", path);
+        } else {
+            println!("No cache at '{}'", path);
+        }
+        return Ok(());
+    }
+
+    if let (Some(dir), Some(manifest)) = (&args.verify_dir, &args.manifest) {
+        let hash_cache = args.cache.as_deref().map(HashCache::load).transpose()?.map(Mutex::new);
+        let started = Instant::now();
+        let report = verify::verify_directory(dir, manifest, Some(algorithm), hash_cache.as_ref())?;
+        let seconds = started.elapsed().as_secs_f64();
+        match &args.prometheus {
+            Some(target) => {
+                metrics::verify_metrics(dir, manifest, &report, seconds).write(target)?;
+                if target != metrics::STDOUT {
+                    report.print();
+                }
+            }
+            None => report.print(),
+        }
+        if let (Some(path), Some(hash_cache)) = (&args.cache, hash_cache) {
+            let hash_cache = hash_cache.into_inner().unwrap();
+            eprintln!("Cache: {} of {} digests reused", hash_cache.hits, hash_cache.hits + hash_cache.misses);
+            hash_cache.save(path)?;
+        }
+        if report.skipped > 0 {
+            std::process::exit(130);
+        }
+        return if report.passed() { Ok(()) } else { Err("verification failed".into()) };
+    }
+
+    if let Some(path) = &args.tree_hash {
+        let threads = args.threads.map_or_else(treehash::default_threads, |threads| threads as usize);
+        return treehash::tree_hash_report(path, args.tree_kind, threads);
+    }
+
+    if let [first, second] = args.compare_json.as_slice() {
+        return if jsondiff::compare_json(first, second, spec)? { Ok(()) } else { Err("the documents differ".into()) };
+    }
+
+    if let (Some(path), Some(key)) = (&args.ndjson, &args.sort_key) {
+        let result = dataset::ndjson_digest(path, key, algorithm)?;
+        audit::record(algorithm, path, &result.digest);
+        if result.runs > 0 && !args.quiet {
+            eprintln!("Sorted on disk in {} runs", result.runs);
+        }
+        if result.duplicate_keys > 0 && !args.quiet {
+            eprintln!("Warning: {} records repeat the previous record's '{}'; it is not unique", result.duplicate_keys, key);
+        }
+        if args.quiet {
+            println!("{}", result.digest);
+        } else {
+            println!("{}  {} ({} records)", result.digest, path, result.records);
+        }
+        return Ok(());
+    }
+
+    if let Some(file) = &args.baseline {
+        let (algorithm, status) = baseline::check_baseline(file, &args.baseline_db, algorithm, args.update_baseline)?;
+        println!("File: '{}'", file);
+        println!("Algorithm: {}", algorithm);
+        return match status {
+            BaselineStatus::Recorded { digest } => {
+                println!("Baseline recorded: {}", digest);
+                Ok(())
+            }
+            BaselineStatus::Unchanged { digest } => {
+                println!("Digest: {}", digest);
+                println!("UNCHANGED since baseline.");
+                Ok(())
+            }
+            BaselineStatus::Changed { old, new } => {
+                println!("Baseline: {}", old);
+                println!("Current:  {}", new);
+                if args.update_baseline {
+                    println!("CHANGED since baseline; baseline updated.");
+                    Ok(())
+                } else {
+                    println!("CHANGED since baseline.");
+                    Err("file changed since baseline".into())
+                }
+            }
+            BaselineStatus::Deleted { old } => {
+                println!("Baseline: {}", old);
+                if args.update_baseline {
+                    println!("DELETED since baseline; baseline removed.");
+                    Ok(())
+                } else {
+                    println!("DELETED since baseline.");
+                    Err("file deleted since baseline".into())
+                }
+            }
+        };
+    }
+
+    #[cfg(feature = "serve")]
+    if let Some(dir) = &args.serve {
+        return serve::serve_manifest(dir, &args.listen, algorithm);
+    }
+
+    #[cfg(feature = "remote")]
+    if let (Some(url), Some(file)) = (&args.check_remote, &args.file) {
+        let check = remote::check_remote(file, url, algorithm)?;
+        if args.quiet {
+            println!("{}", check.actual);
+        } else {
+            println!("File: '{}'", file);
+            println!("Entry: '{}' in {}", check.entry, url);
+            println!("Algorithm: {}", check.algorithm);
+            println!("Published: {}", check.expected);
+            println!("Actual:    {}", check.actual);
+        }
+        return if check.matches() {
+            if !args.quiet {
+                println!("MATCH: the file agrees with the published checksum.");
+            }
+            Ok(())
+        } else {
+            if !args.quiet {
+                println!("MISMATCH: the file does not agree with the published checksum.");
+            }
+            Err("checksum mismatch".into())
+        };
+    }
+
+    if let Some(dir) = &args.scrub {
+        let report = scrub::scrub(dir, &args.scrub_db, algorithm)?;
+        report.print(args.quiet);
+        return match (report.corrupt(), report.unreadable()) {
+            (0, 0) => Ok(()),
+            (corrupt, 0) => Err(format!("{} file(s) corrupt", corrupt).into()),
+            (0, unreadable) => Err(format!("{} file(s) unreadable", unreadable).into()),
+            (corrupt, unreadable) => Err(format!("{} file(s) corrupt, {} unreadable", corrupt, unreadable).into()),
+        };
+    }
+
+    #[cfg(all(target_os = "linux", feature = "procmem"))]
+    if let (Some(pid), Some(range)) = (args.proc_mem, &args.range) {
+        let (start, end) = procmem::parse_range(range)?;
+        let hash = procmem::hash_process_memory(pid, start, end, algorithm)?;
+        let input = format!("pid {} 0x{:x}-0x{:x}", pid, start, end);
+        audit::record(algorithm, &input, &hash);
+        if args.quiet {
+            println!("{}", hash);
+        } else {
+            println!("{}  {}", hash, input);
+        }
+        return Ok(());
+    }
+
+    if let Some(path) = &args.check_fixtures {
+        return match fixtures::check_fixtures(path)? {
+            0 => Ok(()),
+            failures => Err(format!("{} fixture(s) failed", failures).into()),
+        };
+    }
+
+    if let (Some(name), Some(namespace)) = (&args.uuid5, &args.namespace) {
+        println!("{}", uuidv5::uuid_v5(namespace, name));
+        return Ok(());
+    }
+
+    if let Some(format) = args.gen_vectors {
+        vectors::print_vectors(format);
+        return Ok(());
+    }
+
+    if let Some(iterations) = args.fuzz_compare {
+        let algorithm = args.algo.as_ref().map(|_| algorithm);
+        return match fuzz::fuzz_compare(iterations, args.fuzz_seed, algorithm)? {
+            0 => Ok(()),
+            failures => Err(format!("{} inconsistencies found", failures).into()),
+        };
+    }
+
+    // Transforms apply in the order their flags are listed in --help.
+    let mut transforms = Vec::new();
+    if let Some(styles) = args.normalize_source {
+        transforms.push(Transform::NormalizeSource(styles));
+    }
+    if args.case_fold {
+        transforms.push(Transform::CaseFold);
+    }
+    if let Some(key) = &args.xor_key {
+        transforms.push(transform::parse_xor_key(key)?);
+    }
+    if args.compact_size_prefix {
+        transforms.push(Transform::CompactSizePrefix);
+    }
+    if let Some(len) = args.abbrev {
+        if len == 0 {
+            return Err("--abbrev needs at least 1 hex digit".into());
+        }
+        if !args.quiet {
+            eprintln!("Warning: digests abbreviated to {} hex digits ({} bits) are easy to collide", len, len * 4);
+        }
+    }
+    if args.little_endian && !output::has_byte_order(algorithm) {
+        let integers = output::INTEGER_ALGORITHMS.join(" and ");
+        return Err(format!("{} output is a byte string and has no byte order; --little-endian applies to {}", algorithm, integers).into());
+    }
+    if args.uuid.is_some() && spec.hash_bytes(&[])?.len() < 32 {
+        return Err(format!("{} digests are shorter than the 16 bytes a UUID needs", algorithm).into());
+    }
+    let finish = |mut hash: String| {
+        if let Some(out) = &args.out {
+            return out.apply(&hash);
+        }
+        if let Some(style) = args.uuid {
+            return output::format_uuid(&hash, style).unwrap();
+        }
+        if args.little_endian {
+            hash = output::reverse_bytes(&hash);
+        }
+        match args.abbrev {
+            Some(len) => format_abbrev(&hash, len).to_string(),
+            None => hash,
+        }
+    };
+    // The dump, when asked for, shows the exact bytes hashed: after transforms.
+    let digest = |data: Vec<u8>| {
+        let data = transforms.iter().fold(data, |data, transform| transform.apply(data));
+        let dump = args.hexdump.map(|limit| output::hexdump(&data, limit));
+        spec.hash_bytes(&data).map(|hash| (finish(hash), dump))
+    };
+
+    if let Some(path) = &args.follow {
+        let snapshot = |hash: &str, len: u64| {
+            let hash = finish(hash.to_string());
+            if args.quiet {
+                println!("{}", hash);
+            } else {
+                println!("{}  {} bytes", hash, len);
+            }
+        };
+        let restarted = |reason: &str| eprintln!("'{}' was {}; hashing from the start", path, reason);
+        let (hash, len) = follow::follow(path, spec, Duration::from_secs_f64(args.follow_interval), snapshot, restarted)?;
+        let hash = finish(hash);
+        audit::record(&spec.to_string(), path, &hash);
+        if args.quiet {
+            println!("{}", hash);
+        } else {
+            eprintln!("Stopped after {} bytes; final digest:", len);
+            println!("{}", format_coreutils(&hash, path));
+        }
+        return Ok(());
+    }
+
+    if args.tee {
+        let hash = finish(tee(spec)?);
+        audit::record(&spec.to_string(), "stdin (tee)", &hash);
+        if args.quiet {
+            eprintln!("{}", hash);
+        } else {
+            eprintln!("{}", format_coreutils(&hash, "-"));
+        }
+        return Ok(());
+    }
+
+    if let Some(record) = &args.salted {
+        let (data, input) = match (args.text.as_slice(), &args.file, &args.text_file) {
+            ([text], None, None) => (text.as_bytes().to_vec(), format!("{:?}", text)),
+            ([], Some(file), None) => (read_file(file)?, file.clone()),
+            ([], None, Some(path)) => (read_text_file(path)?, path.clone()),
+            _ => return Err("--salted hashes exactly one --text, --file or --text-file input".into()),
+        };
+        let salted = salt::salted_hash(record, &data, &args.salt_db, spec, args.new_salt)?;
+        let hash = finish(salted.digest.clone());
+        audit::record(&spec.to_string(), &format!("{} (salted record '{}')", input, record), &hash);
+        if args.quiet {
+            println!("{}", hash);
+            return Ok(());
+        }
+        println!("Record: {}", record);
+        println!("Salt: {} ({})", hex::encode(&salted.salt), if salted.fresh { "new" } else { "stored" });
+        println!("{}  {}", hash, input);
+        match salted.previous {
+            Some(previous) if previous == salted.digest => println!("MATCH: same digest as the record's last run."),
+            Some(_) => println!("CHANGED: the input differs from the record's last run."),
+            None => {}
+        }
+        return Ok(());
+    }
+
+    let mut results = Vec::new();
+    let mut record_error = None;
+    if !args.text.is_empty() {
+        for (number, text) in args.text.iter().enumerate() {
+            let (text, tag) = match text.rsplit_once(':') {
+                Some((text, tag)) if args.tagged => (text, Some(tag.to_string())),
+                _ => (text.as_str(), None),
+            };
+            let started = Instant::now();
+            let (hash, dump) = digest(text.as_bytes().to_vec())?;
+            let seconds = started.elapsed().as_secs_f64();
+            let line = format!("{}  {:?}", hash, text);
+            let bytes = text.len() as u64;
+            let metric_input = format!("text {}", number + 1);
+            results.push(HashResult { hash, line, input: format!("text ({} bytes)", bytes), tag, dump, metric_input, bytes, seconds });
+        }
+    } else if let Some(file) = &args.file {
+        let started = Instant::now();
+        #[allow(unused_mut)]
+        let mut sparse_hash = None;
+        #[cfg(target_os = "linux")]
+        if args.sparse {
+            let (hash, stats) = sparse::hash_file_sparse(file, algorithm)?;
+            if !args.quiet {
+                if stats.fell_back {
+                    eprintln!("Sparse: not supported on this file system; read {} bytes", stats.read);
+                } else {
+                    eprintln!("Sparse: read {} bytes, skipped {} bytes of holes", stats.read, stats.skipped);
+                }
+            }
+            sparse_hash = Some(finish(hash));
+        }
+        let (hash, dump) = match (sparse_hash, &args.cache, transforms.is_empty()) {
+            (Some(hash), _, _) => (hash, None),
+            (None, _, _) if args.journal.is_some() => {
+                let journal_path = args.journal.as_deref().unwrap();
+                let (hash, start) = journal::hash_file_journaled(file, algorithm, journal_path, args.journal_interval * 1_000_000)?;
+                if !args.quiet {
+                    match start {
+                        Start::Fresh => {}
+                        Start::Resumed { offset } => eprintln!("Journal: resumed at byte {}", offset),
+                        Start::Stale => eprintln!("Journal: '{}' was for another file or version; started over", journal_path),
+                    }
+                }
+                (finish(hash), None)
+            }
+            // Cached digests are of the file as stored, so transforms bypass the cache.
+            (None, Some(path), true) => {
+                let hash_cache = Mutex::new(HashCache::load(path)?);
+                let hash = cache::hash_file_cached(&hash_cache, file, algorithm)?;
+                hash_cache.into_inner().unwrap().save(path)?;
+                (finish(hash), None)
+            }
+            _ => match args.throttle {
+                Some(mb_per_sec) => digest(priority::read_file_throttled(file, mb_per_sec)?)?,
+                None => digest(read_file(file)?)?,
+            },
+        };
+        let seconds = started.elapsed().as_secs_f64();
+        let bytes = fs::metadata(file).map(|metadata| metadata.len()).unwrap_or(0);
+        let line = format_coreutils(&hash, file);
+        results.push(HashResult { hash, line, input: file.clone(), tag: None, dump, metric_input: file.clone(), bytes, seconds });
+    } else if let Some(path) = &args.text_file {
+        let started = Instant::now();
+        let text = read_text_file(path)?;
+        let bytes = text.len() as u64;
+        let (hash, dump) = digest(text)?;
+        let seconds = started.elapsed().as_secs_f64();
+        let line = format_coreutils(&hash, path);
+        results.push(HashResult { hash, line, input: format!("text ({} bytes)", bytes), tag: None, dump, metric_input: path.clone(), bytes, seconds });
+    } else if args.records {
+        let outcome = records::read_records(io::stdin().lock(), |index, record| {
+            if record_error.is_some() {
+                return;
+            }
+            let bytes = record.len() as u64;
+            let started = Instant::now();
+            let (hash, dump) = match digest(record) {
+                Ok(digested) => digested,
+                Err(e) => {
+                    record_error = Some(e);
+                    return;
+                }
+            };
+            let seconds = started.elapsed().as_secs_f64();
+            let line = format!("{}  record {} ({} bytes)", hash, index, bytes);
+            let metric_input = format!("record {}", index);
+            results.push(HashResult { hash, line, input: format!("record {} ({} bytes)", index, bytes), tag: None, dump, metric_input, bytes, seconds });
+        });
+        record_error = record_error.or(outcome.err());
+    } else {
+        return Err("nothing to hash; pass --text, --file, --text-file or --records".into());
+    }
+
+    if args.tagged {
+        // A stable sort keeps each group's inputs in the order given.
+        results.sort_by(|a, b| match (&a.tag, &b.tag) {
+            (Some(a), Some(b)) => a.cmp(b),
+            (a, b) => b.is_some().cmp(&a.is_some()),
+        });
+    }
+
+    let to_stdout = args.prometheus.as_deref() == Some(metrics::STDOUT);
+    let mut group = None;
+    for result in &results {
+        audit::record(&spec.to_string(), &result.input, &result.hash);
+        if to_stdout {
+            continue;
+        }
+        if args.tagged && !args.quiet && group != Some(result.tag.clone()) {
+            if group.is_some() {
+                println!();
+            }
+            println!("[{}]", result.tag.as_deref().unwrap_or("untagged"));
+            group = Some(result.tag.clone());
+        }
+        if args.quiet {
+            println!("{}", result.hash);
+        } else {
+            println!("{}", result.line);
+        }
+        if let Some(dump) = &result.dump {
+            println!("{}", dump);
+        }
+    }
+
+    if let Some(target) = &args.prometheus {
+        let samples: Vec<HashSample> = results
+            .iter()
+            .map(|result| HashSample { input: &result.metric_input, digest: &result.hash, bytes: result.bytes, seconds: result.seconds })
+            .collect();
+        metrics::hash_metrics(&spec.to_string(), &samples).write(target)?;
+    }
+
+    // Records before a malformed one, or one that failed to hash, are still printed.
+    match record_error {
+        Some(e) => Err(e.into()),
+        None => Ok(()),
+    }
+}
+
+/// One digest to report.
+struct HashResult {
+    hash: String,
+    /// The digest labeled with its input, as printed without --quiet.
+    line: String,
+    /// How the audit record names the input.
+    input: String,
+    /// The --tagged group this result is listed under.
+    tag: Option<String>,
+    /// The --hexdump view of the bytes hashed.
+    dump: Option<String>,
+    /// How --prometheus labels the input; unlike `input`, unique per run.
+    metric_input: String,
+    /// Input size and hashing time, for --prometheus.
+    bytes: u64,
+    seconds: f64,
+}
+
+/// Streams stdin to stdout in chunks, hashing as it goes, so it works on
+/// pipes of any length. FarmHash64 has no streaming form and buffers the
+/// whole stream in memory before it can produce a digest.
+fn tee(spec: AlgorithmSpec) -> Result<String, Box<dyn std::error::Error>> {
+    let mut stdin = io::stdin().lock();
+    let mut stdout = io::stdout().lock();
+    let mut hasher = spec.hasher();
+    let mut buffer = [0u8; 64 * 1024];
+
+    loop {
+        let read = match stdin.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(format!("reading stdin: {}", e).into()),
+        };
+        hasher.update(&buffer[..read]);
+        stdout.write_all(&buffer[..read]).map_err(|e| format!("writing stdout: {}", e))?;
+    }
+    stdout.flush().map_err(|e| format!("writing stdout: {}", e))?;
+
+    Ok(hex::encode(hasher.finalize()?))
+}
+
+fn parse_interval(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(secs) if secs.is_finite() && secs > 0.0 => Ok(secs),
+        _ => Err(format!("'{}' is not a positive number of seconds", value)),
+    }
+}
+
+fn parse_throttle(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(rate) if rate.is_finite() && rate > 0.0 => Ok(rate),
+        _ => Err(format!("'{}' is not a positive rate in MB/s", value)),
+    }
+}
+
+fn read_text_file(path: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    if path == "-" {
+        let mut text = Vec::new();
+        io::stdin().read_to_end(&mut text)?;
+        return Ok(text);
+    }
+    fs::read(path).map_err(|e| format!("Cannot read '{}': {}", path, e).into())
+}
@@ -0,0 +1,166 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use hex::encode;
+use serde_json::Value;
+
+use crate::algorithms::{check_file, StreamHasher};
+
+/// Canonical records held in memory before a sorted run is spilled to a
+/// temporary file; exports larger than this are merge-sorted from disk.
+const RUN_BYTES: usize = 64 * 1024 * 1024;
+
+/// The digest of an NDJSON export as a set of records.
+pub struct DatasetDigest {
+    pub digest: String,
+    pub records: u64,
+    /// Records whose sort key equals the previous record's. Their order
+    /// still doesn't matter, but the key is not a unique row ID.
+    pub duplicate_keys: u64,
+    /// Sorted runs spilled to disk; 0 when the export fit in memory.
+    pub runs: usize,
+}
+
+/// The value of the sort key in a record: a top-level field name, or a JSON
+/// Pointer like `/meta/id` for a nested one.
+fn key_value<'a>(record: &'a Value, key: &str) -> Option<&'a Value> {
+    if key.starts_with('/') {
+        record.pointer(key)
+    } else {
+        record.get(key)
+    }
+}
+
+/// Hashes a newline-delimited JSON export (one record per line, blank lines
+/// skipped) so that two dumps of the same rows match whatever order they were
+/// exported in and however their JSON was formatted.
+///
+/// Each record is canonicalized by `serde_json`: object keys sorted,
+/// insignificant whitespace dropped and strings re-escaped uniformly.
+/// Records are then sorted by the canonical text of their `key` value, ties
+/// broken by the whole record, and the digest is taken over the sorted
+/// canonical records, each followed by a newline; that is, the digest of
+/// the canonical export file itself. Integers and floats stay distinct, so
+/// `1` and `1.0` differ, while `1.50` and `1.5` match; integers beyond 64
+/// bits become floats and may lose digits. Keys sort as JSON text, so `10`
+/// comes before `9`.
+///
+/// Exports too big for memory are sorted in runs written to the temporary
+/// directory and merged, so memory use stays near `RUN_BYTES`.
+pub fn ndjson_digest(path: &str, key: &str, algorithm: &str) -> Result<DatasetDigest, Box<dyn std::error::Error>> {
+    check_file(path)?;
+    let file = File::open(path).map_err(|e| format!("Cannot open '{}': {}", path, e))?;
+    let mut runs = SortRuns::new();
+    let mut lines = Vec::new();
+    let mut buffered = 0;
+
+    for (number, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| format!("Cannot read '{}': {}", path, e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: Value = serde_json::from_str(&line)
+            .map_err(|e| format!("'{}' line {}: not valid JSON: {}", path, number + 1, e))?;
+        let sort_key = key_value(&record, key)
+            .ok_or_else(|| format!("'{}' line {}: the record has no '{}'", path, number + 1, key))?;
+
+        // Compact JSON never contains a raw tab, and a tab sorts below every
+        // character it can contain, so sorting these lines as text sorts by
+        // key first and record second.
+        let sort_line = format!("{}\t{}", serde_json::to_string(sort_key)?, serde_json::to_string(&record)?);
+        buffered += sort_line.len();
+        lines.push(sort_line);
+        if buffered >= RUN_BYTES {
+            runs.spill(&mut lines)?;
+            buffered = 0;
+        }
+    }
+
+    let mut hasher = StreamHasher::new(algorithm);
+    let mut records = 0;
+    let mut duplicate_keys = 0;
+    let mut previous_key: Option<String> = None;
+
+    runs.merge(lines, |line| {
+        let (key, record) = line.split_once('\t').unwrap();
+        if previous_key.as_deref() == Some(key) {
+            duplicate_keys += 1;
+        }
+        hasher.update(record.as_bytes());
+        hasher.update(b"\n");
+        records += 1;
+        previous_key = Some(key.to_string());
+    })?;
+
+    Ok(DatasetDigest { digest: encode(hasher.finalize()?), records, duplicate_keys, runs: runs.paths.len() })
+}
+
+/// Sorted runs of lines spilled to temporary files, removed when dropped.
+struct SortRuns {
+    dir: PathBuf,
+    paths: Vec<PathBuf>,
+}
+
+impl SortRuns {
+    fn new() -> Self {
+        SortRuns { dir: std::env::temp_dir(), paths: Vec::new() }
+    }
+
+    fn spill(&mut self, lines: &mut Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
+        lines.sort_unstable();
+        let path = self.dir.join(format!("hashing-demo-sort-{}-{}.ndjson", std::process::id(), self.paths.len()));
+        self.paths.push(path.clone());
+        let mut writer = BufWriter::new(File::create(&path).map_err(|e| format!("Cannot create '{}': {}", path.display(), e))?);
+        for line in lines.drain(..) {
+            writeln!(writer, "{}", line)?;
+        }
+        writer.flush().map_err(|e| format!("Cannot write '{}': {}", path.display(), e))?;
+        Ok(())
+    }
+
+    /// Feeds every line, from the spilled runs and the in-memory `rest`, to
+    /// `each` in sorted order.
+    fn merge(&mut self, mut rest: Vec<String>, mut each: impl FnMut(&str)) -> Result<(), Box<dyn std::error::Error>> {
+        if self.paths.is_empty() {
+            rest.sort_unstable();
+            rest.iter().for_each(|line| each(line));
+            return Ok(());
+        }
+        if !rest.is_empty() {
+            self.spill(&mut rest)?;
+        }
+
+        let mut readers = Vec::with_capacity(self.paths.len());
+        for path in &self.paths {
+            readers.push(open_run(path)?);
+        }
+        let mut heap = BinaryHeap::new();
+        for (run, reader) in readers.iter_mut().enumerate() {
+            if let Some(line) = reader.next().transpose()? {
+                heap.push(Reverse((line, run)));
+            }
+        }
+        while let Some(Reverse((line, run))) = heap.pop() {
+            each(&line);
+            if let Some(next) = readers[run].next().transpose()? {
+                heap.push(Reverse((next, run)));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn open_run(path: &Path) -> Result<std::io::Lines<BufReader<File>>, Box<dyn std::error::Error>> {
+    let file = File::open(path).map_err(|e| format!("Cannot open '{}': {}", path.display(), e))?;
+    Ok(BufReader::new(file).lines())
+}
+
+impl Drop for SortRuns {
+    fn drop(&mut self) {
+        for path in &self.paths {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::HashSet;
+use dialoguer::Input;
+
+use crate::algorithms::{digest_bytes, read_chunks, select_algorithm};
+
+/// Average chunk size offered by default; restic aims for 1 MiB and borg
+/// for 2 MiB, but smaller chunks show deduplication on small demo files.
+const DEFAULT_AVERAGE: usize = 64 * 1024;
+
+/// Splits a byte stream into content-defined chunks with a gear rolling
+/// hash, as FastCDC does: each byte shifts the hash left and adds a random
+/// 64-bit value for that byte, so bit k of the hash depends only on the last
+/// k + 1 bytes. A chunk ends where the hash's top bits are all zero, so the
+/// cut depends on the last 64 bytes; testing the low bits instead would cut
+/// on a window only as wide as the mask, 16 bytes for a 64 KiB average.
+///
+/// Because boundaries depend on content, not offsets, inserting a byte near
+/// the start of a file moves only the boundaries around it; later chunks
+/// are cut in the same places and deduplicate against the old version.
+/// Fixed-size blocks would all shift and none would match.
+pub struct Chunker {
+    gear: [u64; 256],
+    /// 64 minus the number of top hash bits that must be zero at a cut.
+    shift: u32,
+    min: usize,
+    max: usize,
+    hash: u64,
+    current: Vec<u8>,
+}
+
+impl Chunker {
+    /// Chunks average about `average` bytes (rounded down to a power of two)
+    /// and are kept between a quarter and eight times that.
+    pub fn new(average: usize) -> Self {
+        let bits = average.max(64).ilog2();
+        Chunker {
+            gear: gear_table(),
+            shift: 64 - bits,
+            min: (1 << bits) / 4,
+            max: (1 << bits) * 8,
+            hash: 0,
+            current: Vec::new(),
+        }
+    }
+
+    /// Feeds bytes, calling `each` with every chunk they complete.
+    pub fn update(&mut self, data: &[u8], mut each: impl FnMut(&[u8])) {
+        for &byte in data {
+            self.current.push(byte);
+            self.hash = (self.hash << 1).wrapping_add(self.gear[byte as usize]);
+            let len = self.current.len();
+            if len >= self.max || (len >= self.min && self.hash >> self.shift == 0) {
+                each(&self.current);
+                self.current.clear();
+                self.hash = 0;
+            }
+        }
+    }
+
+    /// The final chunk, cut wherever the data ended.
+    pub fn finish(self, mut each: impl FnMut(&[u8])) {
+        if !self.current.is_empty() {
+            each(&self.current);
+        }
+    }
+}
+
+/// 256 fixed pseudo-random values from SplitMix64. Any random table works,
+/// but it must never change: a different table cuts different chunks, and
+/// nothing chunked before would deduplicate again.
+fn gear_table() -> [u64; 256] {
+    let mut state = 0x6861_7368_696e_6721u64;
+    let mut table = [0u64; 256];
+    for entry in table.iter_mut() {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        *entry = z ^ (z >> 31);
+    }
+    table
+}
+
+/// Chunk counts for one file added to the store.
+pub struct FileDedup {
+    pub chunks: usize,
+    pub new_chunks: usize,
+    pub bytes: u64,
+    pub new_bytes: u64,
+}
+
+/// The set of chunk digests a backup repository already holds.
+#[derive(Default)]
+pub struct ChunkStore {
+    seen: HashSet<Vec<u8>>,
+}
+
+impl ChunkStore {
+    /// Chunks a file and adds its chunks to the store, counting how many
+    /// were already there and so would not be stored again.
+    pub fn add_file(&mut self, path: &str, average: usize, algorithm: &str) -> Result<FileDedup, Box<dyn std::error::Error>> {
+        let mut chunker = Chunker::new(average);
+        let mut result = FileDedup { chunks: 0, new_chunks: 0, bytes: 0, new_bytes: 0 };
+        let mut failure = None;
+        let mut store = |chunk: &[u8]| {
+            result.chunks += 1;
+            result.bytes += chunk.len() as u64;
+            match digest_bytes(chunk, algorithm) {
+                Ok(digest) => {
+                    if self.seen.insert(digest) {
+                        result.new_chunks += 1;
+                        result.new_bytes += chunk.len() as u64;
+                    }
+                }
+                Err(e) => {
+                    failure.get_or_insert(e);
+                }
+            }
+        };
+        read_chunks(path, |data| chunker.update(data, &mut store))?;
+        chunker.finish(&mut store);
+        match failure {
+            Some(e) => Err(e.into()),
+            None => Ok(result),
+        }
+    }
+
+    pub fn unique_chunks(&self) -> usize {
+        self.seen.len()
+    }
+}
+
+/// Feeds files one after another into a chunk store, as successive backups
+/// would, and reports what each one adds. Backing up a file and then an
+/// edited copy shows the second costing only the chunks around the edit.
+pub fn dedup_report() {
+    let average: usize = Input::new()
+        .with_prompt("Average chunk size in bytes (a power of two)")
+        .default(DEFAULT_AVERAGE)
+        .validate_with(|average: &usize| if *average >= 64 { Ok(()) } else { Err("must be at least 64") })
+        .interact_text()
+        .unwrap();
+    let algorithm = select_algorithm();
+
+    let mut store = ChunkStore::default();
+    let (mut chunks, mut bytes, mut stored) = (0usize, 0u64, 0u64);
+    loop {
+        let path: String = Input::new()
+            .with_prompt("File to back up (empty to finish)")
+            .allow_empty(true)
+            .interact_text()
+            .unwrap();
+        if path.trim().is_empty() {
+            break;
+        }
+        match store.add_file(path.trim(), average, algorithm) {
+            Ok(file) => {
+                println!(
+                    "  {} chunks, {} new; stores {} of {} bytes",
+                    file.chunks, file.new_chunks, file.new_bytes, file.bytes
+                );
+                chunks += file.chunks;
+                bytes += file.bytes;
+                stored += file.new_bytes;
+            }
+            Err(e) => eprintln!("Error: {}", e),
+        }
+    }
+    if chunks == 0 {
+        return;
+    }
+
+    let bits = average.max(64).ilog2();
+    println!("\nAverage chunk size: {} bytes (min {}, max {})", 1usize << bits, (1usize << bits) / 4, (1usize << bits) * 8);
+    println!("Chunk digests: {}", algorithm);
+    println!("Chunks: {}", chunks);
+    println!("Unique chunks: {}", store.unique_chunks());
+    println!("Bytes in: {}, stored: {}", bytes, stored);
+    println!("Dedup ratio: {:.2}x\n", bytes as f64 / stored.max(1) as f64);
+    println!("Backup tools like restic and borg store each unique chunk once under its");
+    println!("digest; a file's backup is just its list of chunk digests.\n");
+}
@@ -0,0 +1,37 @@
+use std::panic;
+
+use crate::algorithms::{hash_text, read_file};
+
+/// Extracts the text of a PDF and hashes it with whitespace normalized. This
+/// is a content hash, not a byte hash: two PDFs with the same words hash the
+/// same even if their fonts, layout, metadata or producer differ, while a
+/// change to the file that leaves the text alone goes unnoticed.
+///
+/// Normalization collapses every run of whitespace, including line and page
+/// breaks, to a single space and trims both ends, since extractors disagree
+/// on where one line ends and the next begins.
+///
+/// Returns the digest and the normalized text it covers.
+pub fn pdf_text_digest(path: &str, algorithm: &str) -> Result<(String, String), Box<dyn std::error::Error>> {
+    let data = read_file(path)?;
+
+    // pdf-extract panics on some malformed or exotic PDFs instead of
+    // returning an error; treat that as a failed extraction.
+    let previous_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let extracted = panic::catch_unwind(|| pdf_extract::extract_text_from_mem(&data));
+    panic::set_hook(previous_hook);
+
+    let text = match extracted {
+        Ok(Ok(text)) => text,
+        Ok(Err(e)) => return Err(format!("Cannot extract text from '{}': {}", path, e).into()),
+        Err(_) => return Err(format!("Cannot extract text from '{}': unsupported or malformed PDF", path).into()),
+    };
+
+    let normalized = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if normalized.is_empty() {
+        return Err(format!("'{}' has no extractable text (it may be scanned images)", path).into());
+    }
+
+    Ok((hash_text(&normalized, algorithm)?, normalized))
+}
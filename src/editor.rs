@@ -0,0 +1,58 @@
+use dialoguer::{Editor, Select};
+
+use crate::algorithms::{hash_text, select_algorithm};
+
+/// Opens `$VISUAL`/`$EDITOR` (falling back to `vi`, or `notepad` on Windows)
+/// on a buffer and hashes what was saved, for multiline or pasted input that
+/// a single-line prompt can't take. Each round re-opens the previous buffer,
+/// so the same text can be tweaked and re-hashed in place.
+///
+/// Trailing newlines are stripped, since most editors add one on save;
+/// everything else, including `\r\n` line endings inside the text, is hashed
+/// as written. Quitting the editor without saving cancels the round and
+/// keeps the previous buffer.
+pub fn edit_and_hash() {
+    let algorithm = select_algorithm();
+    let mut buffer = String::new();
+    let mut previous_hash: Option<String> = None;
+
+    loop {
+        match Editor::new().edit(&buffer) {
+            Ok(Some(edited)) => {
+                buffer = edited;
+                let hash = match hash_text(&buffer, algorithm) {
+                    Ok(hash) => hash,
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        return;
+                    }
+                };
+
+                println!("\nAlgorithm: {}", algorithm);
+                println!("Edited input: {} bytes, {} lines", buffer.len(), buffer.lines().count());
+                println!("Hash: {}", hash);
+                match &previous_hash {
+                    Some(previous) if *previous == hash => println!("Unchanged from the previous edit."),
+                    Some(previous) => println!("Previous: {}", previous),
+                    None => {}
+                }
+                println!();
+                previous_hash = Some(hash);
+            }
+            Ok(None) => println!("\nEditor closed without saving; the buffer is unchanged.\n"),
+            Err(e) => {
+                eprintln!("Error: could not run the editor: {}", e);
+                return;
+            }
+        }
+
+        let again = Select::new()
+            .items(&["Edit Again", "Done"])
+            .default(0)
+            .interact()
+            .unwrap();
+        if again == 1 {
+            return;
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use dialoguer::{Input, Select};
+use hex::encode;
+
+use crate::algorithms::digest_bytes;
+
+pub fn ethereum() {
+    let ethereum_choices = vec![
+        "Function Selector",
+        "Event Topic",
+        "CREATE2 Address",
+        "Checksum Address (EIP-55)",
+        "Validate Address Checksum",
+    ];
+    let ethereum_selection = Select::new()
+        .with_prompt("Choose an Ethereum mode")
+        .items(&ethereum_choices)
+        .default(0)
+        .interact()
+        .unwrap();
+
+    match ethereum_selection {
+        0 => function_selector(),
+        1 => event_topic(),
+        2 => create2_address_interactive(),
+        3 => checksum_address_interactive(),
+        4 => validate_checksum_interactive(),
+        _ => unreachable!(),
+    }
+}
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    digest_bytes(data, "Keccak-256").unwrap().try_into().unwrap()
+}
+
+/// The hex digits of `input`, without surrounding whitespace or a `0x`.
+fn hex_digits(input: &str) -> &str {
+    let input = input.trim();
+    input.strip_prefix("0x").or_else(|| input.strip_prefix("0X")).unwrap_or(input)
+}
+
+/// Parses `len` bytes of hex, with or without a `0x` prefix.
+pub fn parse_hex_exact(input: &str, len: usize) -> Result<Vec<u8>, String> {
+    let bytes = hex::decode(hex_digits(input)).map_err(|e| format!("invalid hex: {}", e))?;
+    if bytes.len() != len {
+        return Err(format!("expected {} bytes ({} hex digits), got {}", len, len * 2, bytes.len()));
+    }
+    Ok(bytes)
+}
+
+/// EIP-55 mixed-case checksum encoding: each letter of the lowercase hex
+/// address is uppercased when the matching nibble of the Keccak-256 of that
+/// lowercase hex string is 8 or more.
+pub fn to_checksum_address(address: &[u8; 20]) -> String {
+    let lower = encode(address);
+    let digest = keccak256(lower.as_bytes());
+    let checksummed: String = lower
+        .chars()
+        .enumerate()
+        .map(|(index, c)| {
+            let nibble = (digest[index / 2] >> (if index % 2 == 0 { 4 } else { 0 })) & 0x0f;
+            if nibble >= 8 { c.to_ascii_uppercase() } else { c }
+        })
+        .collect();
+    format!("0x{}", checksummed)
+}
+
+/// Outcome of checking an address's EIP-55 checksum.
+pub enum ChecksumStatus {
+    Valid,
+    /// All lowercase or all uppercase: a valid address that carries no
+    /// checksum, so a typo in it can't be detected.
+    NoChecksum,
+    /// Mixed case that doesn't match; holds the correctly checksummed form.
+    Invalid { expected: String },
+}
+
+/// Checks a 0x-prefixed (or bare) 40-digit address against EIP-55.
+pub fn check_checksum_address(input: &str) -> Result<ChecksumStatus, String> {
+    let address: [u8; 20] = parse_hex_exact(input, 20)?.try_into().unwrap();
+    let digits = hex_digits(input);
+    if !digits.chars().any(|c| c.is_ascii_uppercase()) || !digits.chars().any(|c| c.is_ascii_lowercase()) {
+        return Ok(ChecksumStatus::NoChecksum);
+    }
+
+    let expected = to_checksum_address(&address);
+    if expected[2..] == *digits {
+        Ok(ChecksumStatus::Valid)
+    } else {
+        Ok(ChecksumStatus::Invalid { expected })
+    }
+}
+
+/// The address a contract deployed with CREATE2 gets (EIP-1014): the last 20
+/// bytes of `keccak256(0xff ++ deployer ++ salt ++ keccak256(init_code))`.
+/// It depends only on these three values, not on the deployer's nonce, so it
+/// is known before deployment.
+pub fn create2_address(deployer: &[u8; 20], salt: &[u8; 32], init_code_hash: &[u8; 32]) -> [u8; 20] {
+    let preimage = [&[0xff][..], deployer, salt, init_code_hash].concat();
+    keccak256(&preimage)[12..].try_into().unwrap()
+}
+
+/// Checks a canonical ABI signature, `name(type1,type2,...)`: the form that
+/// is hashed has no spaces, no parameter names and no `indexed` markers, so
+/// `transfer(address to, uint256 amount)` must be written
+/// `transfer(address,uint256)`. Types themselves aren't checked against the
+/// ABI's list, but aliases like `uint` are rejected since they hash
+/// differently from the canonical `uint256`.
+pub fn check_signature(signature: &str) -> Result<(), String> {
+    if signature.chars().any(char::is_whitespace) {
+        return Err("the canonical signature has no spaces, e.g. transfer(address,uint256)".into());
+    }
+    let Some((name, rest)) = signature.split_once('(') else {
+        return Err("expected name(type,...)".into());
+    };
+    let Some(params) = rest.strip_suffix(')') else {
+        return Err("the signature must end with ')'".into());
+    };
+
+    let mut chars = name.chars();
+    let valid_start = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$');
+    if !valid_start || !chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$') {
+        return Err(format!("'{}' is not a valid function or event name", name));
+    }
+
+    let mut depth = 0i32;
+    for c in params.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        if depth < 0 {
+            return Err("unbalanced parentheses".into());
+        }
+    }
+    if depth != 0 {
+        return Err("unbalanced parentheses".into());
+    }
+
+    for param in params.split([',', '(', ')']).filter(|param| !param.is_empty()) {
+        let base = param.split('[').next().unwrap();
+        if matches!(base, "uint" | "int" | "byte" | "fixed" | "ufixed") {
+            return Err(format!("'{}' is an alias; write the canonical type (uint256, int256, bytes1, ...)", base));
+        }
+    }
+    Ok(())
+}
+
+fn read_signature(prompt: &str, default: &str) -> String {
+    Input::new()
+        .with_prompt(prompt)
+        .default(default.to_string())
+        .validate_with(|signature: &String| check_signature(signature))
+        .interact_text()
+        .unwrap()
+}
+
+/// The ABI function selector: the first 4 bytes of the Keccak-256 of the
+/// canonical signature, which a call's calldata starts with.
+fn function_selector() {
+    let signature = read_signature("Function signature", "transfer(address,uint256)");
+    let digest = keccak256(signature.as_bytes());
+
+    println!("\nSignature: {}", signature);
+    println!("Keccak-256: {}", encode(digest));
+    println!("Selector:   0x{}\n", encode(&digest[..4]));
+    println!("Calldata for this function starts with the selector, then the ABI-encoded");
+    println!("arguments. Only 32 bits are kept, so unrelated signatures can share one.\n");
+}
+
+/// An event's topic 0: the full 32-byte Keccak-256 of its canonical
+/// signature, which log filters match on.
+fn event_topic() {
+    let signature = read_signature("Event signature", "Transfer(address,address,uint256)");
+    let digest = keccak256(signature.as_bytes());
+
+    println!("\nSignature: {}", signature);
+    println!("Topic 0:   0x{}\n", encode(digest));
+    println!("Every non-anonymous log of this event has this value as its first topic.\n");
+}
+
+fn read_hex_exact(prompt: &str, len: usize, default: &str) -> Vec<u8> {
+    let input: String = Input::new()
+        .with_prompt(prompt)
+        .default(default.to_string())
+        .validate_with(|input: &String| parse_hex_exact(input, len).map(|_| ()))
+        .interact_text()
+        .unwrap();
+    parse_hex_exact(&input, len).unwrap()
+}
+
+/// Defaults are the first example of EIP-1014, with init code `0x00`.
+fn create2_address_interactive() {
+    let deployer = read_hex_exact("Deployer address (20 bytes hex)", 20, "0x0000000000000000000000000000000000000000");
+    let salt = read_hex_exact("Salt (32 bytes hex)", 32, &format!("0x{}", "00".repeat(32)));
+    let init_code_hash = read_hex_exact(
+        "Init code hash, keccak256(init_code) (32 bytes hex)",
+        32,
+        "0xbc36789e7a1e281436464229828f817d6612f7b477d66591ff96a9e064bcc98a",
+    );
+
+    let deployer: [u8; 20] = deployer.try_into().unwrap();
+    let salt: [u8; 32] = salt.try_into().unwrap();
+    let init_code_hash: [u8; 32] = init_code_hash.try_into().unwrap();
+    let address = create2_address(&deployer, &salt, &init_code_hash);
+
+    println!("\nDeployer:        {}", to_checksum_address(&deployer));
+    println!("Salt:            0x{}", encode(salt));
+    println!("Init code hash:  0x{}", encode(init_code_hash));
+    println!("CREATE2 address: {}\n", to_checksum_address(&address));
+    println!("keccak256(0xff ++ deployer ++ salt ++ init code hash), last 20 bytes. The same");
+    println!("three inputs give the same address on every chain.\n");
+}
+
+fn checksum_address_interactive() {
+    let address = read_hex_exact("Address (20 bytes hex)", 20, "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed");
+    let address: [u8; 20] = address.try_into().unwrap();
+
+    println!("\nLowercase: 0x{}", encode(address));
+    println!("EIP-55:    {}\n", to_checksum_address(&address));
+    println!("Each letter is uppercased where the matching nibble of Keccak-256 of the");
+    println!("lowercase hex is 8 or more, so a mistyped address almost never checks out.\n");
+}
+
+fn validate_checksum_interactive() {
+    let address: String = Input::new()
+        .with_prompt("Mixed-case address")
+        .validate_with(|address: &String| parse_hex_exact(address, 20).map(|_| ()))
+        .interact_text()
+        .unwrap();
+
+    match check_checksum_address(&address).unwrap() {
+        ChecksumStatus::Valid => println!("\nVALID: the EIP-55 checksum matches.\n"),
+        ChecksumStatus::NoChecksum => {
+            println!("\nNO CHECKSUM: the address is all one case, so EIP-55 can't vouch for it.");
+            let bytes: [u8; 20] = parse_hex_exact(&address, 20).unwrap().try_into().unwrap();
+            println!("Checksummed: {}\n", to_checksum_address(&bytes));
+        }
+        ChecksumStatus::Invalid { expected } => {
+            let digits = hex_digits(&address);
+            let marks: String = digits
+                .chars()
+                .zip(expected[2..].chars())
+                .map(|(given, wanted)| if given == wanted { ' ' } else { '^' })
+                .collect();
+            println!("\nINVALID: the checksum does not match; the address may be mistyped.");
+            println!("Given:    0x{}", digits);
+            println!("Expected: {}", expected);
+            println!("            {}\n", marks);
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use std::fs;
+use serde::Deserialize;
+
+use crate::algorithms::AlgorithmSpec;
+
+/// One expected result: `input` is hashed as UTF-8 text, or `input_hex` as
+/// raw bytes when the input isn't text.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Fixture {
+    input: Option<String>,
+    input_hex: Option<String>,
+    algorithm: String,
+    expected_digest: String,
+}
+
+/// Recomputes every fixture in a JSON array of
+/// `{"input", "algorithm", "expected_digest"}` objects and prints one line per
+/// fixture. The algorithm may be a spec such as `blake2b:32`. Returns the
+/// number that failed, whether by mismatch or by naming an algorithm this
+/// build doesn't have.
+pub fn check_fixtures(path: &str) -> Result<usize, Box<dyn std::error::Error>> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("Cannot read fixtures '{}': {}", path, e))?;
+    let fixtures: Vec<Fixture> =
+        serde_json::from_str(&contents).map_err(|e| format!("Fixtures '{}' are not valid: {}", path, e))?;
+
+    let mut failures = 0;
+    for (index, fixture) in fixtures.iter().enumerate() {
+        match check_fixture(fixture) {
+            Ok(None) => println!("ok        #{} {}", index, fixture.algorithm),
+            Ok(Some(actual)) => {
+                failures += 1;
+                println!("MISMATCH  #{} {}", index, fixture.algorithm);
+                println!("          expected {}", fixture.expected_digest);
+                println!("          actual   {}", actual);
+            }
+            Err(e) => {
+                failures += 1;
+                println!("ERROR     #{}: {}", index, e);
+            }
+        }
+    }
+
+    println!("\n{} fixture(s), {} passed, {} failed", fixtures.len(), fixtures.len() - failures, failures);
+    Ok(failures)
+}
+
+/// `None` when the fixture passes, otherwise the digest actually computed.
+fn check_fixture(fixture: &Fixture) -> Result<Option<String>, String> {
+    let spec = AlgorithmSpec::parse(&fixture.algorithm)?;
+    let input = match (&fixture.input, &fixture.input_hex) {
+        (Some(text), None) => text.as_bytes().to_vec(),
+        (None, Some(hex_input)) => hex::decode(hex_input).map_err(|e| format!("invalid input_hex: {}", e))?,
+        _ => return Err("give exactly one of input and input_hex".into()),
+    };
+
+    let actual = spec.hash_bytes(&input)?;
+    if actual.eq_ignore_ascii_case(fixture.expected_digest.trim()) { Ok(None) } else { Ok(Some(actual)) }
+}
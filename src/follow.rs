@@ -0,0 +1,99 @@
+use std::fs::{self, File, Metadata};
+use std::io::{self, Read};
+use std::thread;
+use std::time::Duration;
+
+use crate::algorithms::{check_file, AlgorithmSpec, StreamHasher};
+use crate::interrupt;
+
+/// How long to wait between checks for new data, in small steps so Ctrl-C
+/// is noticed promptly.
+const POLL_STEP: Duration = Duration::from_millis(100);
+
+/// Identity of the file behind a path, to notice when a log is rotated and
+/// the path now names a new file. Only Unix exposes inode numbers; elsewhere
+/// rotation shows up only if the new file is smaller.
+fn file_id(metadata: &Metadata) -> Option<(u64, u64)> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        Some((metadata.dev(), metadata.ino()))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        None
+    }
+}
+
+/// Hashes a file that is still being written, like `tail -f`: everything in
+/// it now, then whatever is appended, calling `snapshot` with the digest of
+/// all bytes so far and their count each time the file has grown. Only new
+/// bytes are read; the running hash is cloned for each snapshot.
+///
+/// If the file shrinks (truncated in place) or the path starts naming a
+/// different file (rotated), the old contents are gone and the hash starts
+/// over on what is there now, after a call to `restarted`. Runs until Ctrl-C
+/// and returns the final digest and length.
+pub fn follow(
+    path: &str,
+    spec: AlgorithmSpec,
+    interval: Duration,
+    mut snapshot: impl FnMut(&str, u64),
+    mut restarted: impl FnMut(&str),
+) -> Result<(String, u64), Box<dyn std::error::Error>> {
+    check_file(path)?;
+    let _guard = interrupt::catch();
+
+    let open = |path: &str| -> io::Result<(File, Option<(u64, u64)>)> {
+        let file = File::open(path)?;
+        let id = file_id(&file.metadata()?);
+        Ok((file, id))
+    };
+    let (mut file, mut id) = open(path).map_err(|e| format!("Cannot open '{}': {}", path, e))?;
+    let mut hasher: StreamHasher = spec.hasher();
+    let mut len = 0u64;
+    let mut buffer = vec![0u8; 64 * 1024];
+
+    loop {
+        let mut grew = false;
+        loop {
+            let read = match file.read(&mut buffer) {
+                Ok(read) => read,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(format!("Cannot read '{}': {}", path, e).into()),
+            };
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+            len += read as u64;
+            grew = true;
+        }
+        if grew {
+            snapshot(&hex::encode(hasher.clone().finalize()?), len);
+        }
+
+        let mut waited = Duration::ZERO;
+        while waited < interval {
+            if interrupt::interrupted() {
+                return Ok((hex::encode(hasher.finalize()?), len));
+            }
+            thread::sleep(POLL_STEP);
+            waited += POLL_STEP;
+        }
+
+        // Between rotation steps the path may briefly not exist; keep
+        // reading the old file until a new one appears.
+        let Ok(metadata) = fs::metadata(path) else {
+            continue;
+        };
+        let rotated = file_id(&metadata) != id;
+        if rotated || metadata.len() < len {
+            restarted(if rotated { "replaced by a new file" } else { "truncated" });
+            (file, id) = open(path).map_err(|e| format!("Cannot open '{}': {}", path, e))?;
+            hasher = spec.hasher();
+            len = 0;
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use image::{Rgb, RgbImage};
+
+/// Cells per side of the identicon grid.
+const GRID: u32 = 5;
+/// Size of one grid cell and of the margin around the grid, in pixels.
+const CELL: u32 = 50;
+const MARGIN: u32 = 25;
+const BACKGROUND: Rgb<u8> = Rgb([240, 240, 240]);
+
+/// Writes a GitHub-style identicon for the digest as a PNG: a 5x5 grid,
+/// mirrored left to right, drawn in one color on a light background.
+///
+/// The first three digest bytes give the color, blended halfway toward gray
+/// so it never vanishes into the background, and the bits of the next two
+/// bytes switch on the 15 cells of the left three columns, which are mirrored
+/// onto the right two. The same digest always gives the same image.
+pub fn write_identicon(hash: &str, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let bytes = hex::decode(hash)?;
+    if bytes.len() < 5 {
+        return Err("an identicon needs a digest of at least 5 bytes".into());
+    }
+
+    let color = Rgb([mute(bytes[0]), mute(bytes[1]), mute(bytes[2])]);
+    let cells = u16::from_be_bytes([bytes[3], bytes[4]]);
+
+    let size = GRID * CELL + 2 * MARGIN;
+    let mut image = RgbImage::from_pixel(size, size, BACKGROUND);
+    for row in 0..GRID {
+        for column in 0..GRID.div_ceil(2) {
+            if cells >> (row * 3 + column) & 1 == 0 {
+                continue;
+            }
+            for mirrored in [column, GRID - 1 - column] {
+                fill_cell(&mut image, row, mirrored, color);
+            }
+        }
+    }
+
+    image.save(path).map_err(|e| format!("Cannot write '{}': {}", path, e).into())
+}
+
+fn mute(channel: u8) -> u8 {
+    ((channel as u16 + 128) / 2) as u8
+}
+
+fn fill_cell(image: &mut RgbImage, row: u32, column: u32, color: Rgb<u8>) {
+    for y in 0..CELL {
+        for x in 0..CELL {
+            image.put_pixel(MARGIN + column * CELL + x, MARGIN + row * CELL + y, color);
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use dialoguer::{FuzzySelect, Input, Select};
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::algorithms::read_file;
+
+/// Reads bytes typed as text, or as hex when prefixed with `0x`.
+pub fn read_bytes(prompt: &str, allow_empty: bool) -> Vec<u8> {
+    loop {
+        let input: String = Input::new()
+            .with_prompt(prompt)
+            .allow_empty(allow_empty)
+            .interact_text()
+            .unwrap();
+
+        match parse_bytes(&input) {
+            Ok(bytes) => return bytes,
+            Err(e) => eprintln!("Error: {}", e),
+        }
+    }
+}
+
+/// Like `read_bytes`, for secrets: the typed string and the decoded bytes
+/// are wiped when dropped. Copies made inside dialoguer while editing the
+/// line are out of our reach and are not wiped.
+pub fn read_secret_bytes(prompt: &str) -> Zeroizing<Vec<u8>> {
+    loop {
+        let mut input: String = Input::new()
+            .with_prompt(prompt)
+            .interact_text()
+            .unwrap();
+
+        let parsed = parse_bytes(&input);
+        input.zeroize();
+        match parsed {
+            Ok(bytes) => return Zeroizing::new(bytes),
+            Err(e) => eprintln!("Error: {}", e),
+        }
+    }
+}
+
+pub fn parse_bytes(input: &str) -> Result<Vec<u8>, String> {
+    match input.strip_prefix("0x") {
+        Some(hex) => hex::decode(hex).map_err(|e| format!("invalid hex after 0x: {}", e)),
+        None => Ok(input.as_bytes().to_vec()),
+    }
+}
+
+/// Asks whether to read text or a file and returns a label for the input
+/// along with its bytes.
+pub fn read_text_or_file() -> Result<(String, Vec<u8>), Box<dyn std::error::Error>> {
+    let source = Select::new()
+        .with_prompt("Input source")
+        .items(&["Text", "File"])
+        .default(0)
+        .interact()
+        .unwrap();
+
+    match source {
+        0 => {
+            let text: String = Input::new()
+                .with_prompt("Enter text")
+                .allow_empty(true)
+                .interact_text()
+                .unwrap();
+            Ok((format!("text ({} bytes)", text.len()), text.into_bytes()))
+        }
+        1 => {
+            let path: String = Input::new()
+                .with_prompt("Enter file path")
+                .interact_text()
+                .unwrap();
+            let data = read_file(&path)?;
+            Ok((format!("'{}'", path), data))
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// Browses from the current directory with a fuzzy-filtered list, descending
+/// into directories until a file is chosen. Returns `None` if the user
+/// backs out with Esc.
+pub fn pick_file() -> Option<String> {
+    let mut current = PathBuf::new();
+
+    loop {
+        let shown = if current.as_os_str().is_empty() { Path::new(".") } else { current.as_path() };
+        let mut entries = match list_entries(shown) {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!("Error: cannot list '{}': {}", shown.display(), e);
+                if !current.pop() {
+                    return None;
+                }
+                continue;
+            }
+        };
+        entries.insert(0, ("../".to_string(), true));
+
+        let labels: Vec<&str> = entries.iter().map(|(label, _)| label.as_str()).collect();
+        let selection = FuzzySelect::new()
+            .with_prompt(format!("{} (type to filter, Esc to cancel)", shown.display()))
+            .items(&labels)
+            .default(0)
+            .interact_opt()
+            .unwrap()?;
+
+        let (label, is_dir) = &entries[selection];
+        if selection == 0 && current.file_name().is_some() {
+            current.pop();
+        } else if *is_dir {
+            current.push(label.trim_end_matches('/'));
+        } else {
+            return Some(current.join(label).to_string_lossy().into_owned());
+        }
+    }
+}
+
+/// A directory's entries, directories first and marked with a trailing `/`.
+fn list_entries(dir: &Path) -> std::io::Result<Vec<(String, bool)>> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        // Follows symlinks, so a link to a directory can be descended into.
+        let is_dir = entry.path().is_dir();
+        let mut label = entry.file_name().to_string_lossy().into_owned();
+        if is_dir {
+            label.push('/');
+        }
+        entries.push((label, is_dir));
+    }
+    entries.sort_by(|(a, a_dir), (b, b_dir)| b_dir.cmp(a_dir).then_with(|| a.cmp(b)));
+    Ok(entries)
+}
@@ -0,0 +1,36 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
+
+static CATCHING: AtomicBool = AtomicBool::new(false);
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+static INSTALL: Once = Once::new();
+
+/// While this guard is alive, Ctrl-C sets a flag for long-running work to
+/// poll instead of killing the process. Outside a guard Ctrl-C exits as usual.
+pub struct Guard;
+
+pub fn catch() -> Guard {
+    INSTALL.call_once(|| {
+        let _ = ctrlc::set_handler(|| {
+            if CATCHING.load(Ordering::SeqCst) {
+                INTERRUPTED.store(true, Ordering::SeqCst);
+            } else {
+                std::process::exit(130);
+            }
+        });
+    });
+
+    INTERRUPTED.store(false, Ordering::SeqCst);
+    CATCHING.store(true, Ordering::SeqCst);
+    Guard
+}
+
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        CATCHING.store(false, Ordering::SeqCst);
+    }
+}
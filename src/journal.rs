@@ -0,0 +1,202 @@
+use std::fs::{self, File};
+use std::io::{Seek, SeekFrom};
+use std::path::{self, Path};
+use std::time::UNIX_EPOCH;
+use serde::{Deserialize, Serialize};
+use sha2::digest::generic_array::GenericArray;
+
+use crate::algorithms::{self, check_file, sha256_padding, SHA256_IV};
+use crate::interrupt;
+use crate::records::read_full;
+
+/// Algorithms whose running state is small and plain enough to write to a
+/// journal: SHA-256's eight chaining words and CRC32's register. The other
+/// hashers keep private state (or, for FarmHash64, the whole input).
+pub const JOURNAL_ALGORITHMS: &[&str] = &["SHA-256", "CRC32"];
+
+/// Read size; a multiple of SHA-256's 64-byte block, so the state between
+/// chunks is always a plain chaining value with nothing buffered.
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// A checkpoint of a partly hashed file. It is only resumed for the same
+/// file, unchanged since, with the same algorithm.
+#[derive(Serialize, Deserialize)]
+struct Journal {
+    path: String,
+    size: u64,
+    mtime_ns: u128,
+    algorithm: String,
+    offset: u64,
+    /// The hasher state in hex: SHA-256's words or CRC32's value, big-endian.
+    state: String,
+}
+
+enum State {
+    Sha256([u32; 8]),
+    Crc32(u32),
+}
+
+impl State {
+    fn new(algorithm: &str) -> Self {
+        match algorithm {
+            "SHA-256" => State::Sha256(SHA256_IV),
+            _ => State::Crc32(0),
+        }
+    }
+
+    fn decode(algorithm: &str, state: &str) -> Option<Self> {
+        let bytes = hex::decode(state).ok()?;
+        match algorithm {
+            "SHA-256" if bytes.len() == 32 => {
+                let mut words = [0u32; 8];
+                for (word, bytes) in words.iter_mut().zip(bytes.chunks_exact(4)) {
+                    *word = u32::from_be_bytes(bytes.try_into().unwrap());
+                }
+                Some(State::Sha256(words))
+            }
+            "CRC32" if bytes.len() == 4 => Some(State::Crc32(u32::from_be_bytes(bytes.try_into().unwrap()))),
+            _ => None,
+        }
+    }
+
+    fn encode(&self) -> String {
+        match self {
+            State::Sha256(words) => words.iter().map(|word| format!("{:08x}", word)).collect(),
+            State::Crc32(crc) => format!("{:08x}", crc),
+        }
+    }
+
+    /// Feeds whole chunks; for SHA-256 `data` must be a multiple of 64 bytes.
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            State::Sha256(words) => {
+                for block in data.chunks_exact(64) {
+                    sha2::compress256(words, &[*GenericArray::from_slice(block)]);
+                }
+            }
+            State::Crc32(crc) => {
+                let mut hasher = crc32fast::Hasher::new_with_initial(*crc);
+                hasher.update(data);
+                *crc = hasher.finalize();
+            }
+        }
+    }
+
+    /// Feeds the last, possibly partial, chunk and returns the hex digest of
+    /// all `total_len` bytes.
+    fn finalize(mut self, tail: &[u8], total_len: u64) -> String {
+        match &mut self {
+            State::Sha256(_) => {
+                let padded = [tail, &sha256_padding(total_len)].concat();
+                self.update(&padded);
+            }
+            State::Crc32(_) => self.update(tail),
+        }
+        self.encode()
+    }
+}
+
+/// How a journaled hash started.
+pub enum Start {
+    Fresh,
+    Resumed { offset: u64 },
+    /// A journal was there but for another file, algorithm, or an older
+    /// version of this file; it was discarded.
+    Stale,
+}
+
+/// Hashes a file in chunks, writing the hasher state and byte offset to the
+/// journal at `journal_path` after every `interval` bytes. If the journal
+/// already holds a checkpoint for this file, unchanged by size and mtime, the
+/// hash resumes from there instead of the start. Ctrl-C writes a final
+/// checkpoint before stopping. The journal is removed once the digest is
+/// complete.
+pub fn hash_file_journaled(
+    file: &str,
+    algorithm: &str,
+    journal_path: &str,
+    interval: u64,
+) -> Result<(String, Start), Box<dyn std::error::Error>> {
+    let algorithm = algorithms::lookup(algorithm)?;
+    if !JOURNAL_ALGORITHMS.contains(&algorithm) {
+        return Err(format!("{} state can't be journaled; use {}", algorithm, JOURNAL_ALGORITHMS.join(" or ")).into());
+    }
+    check_file(file)?;
+
+    let metadata = fs::metadata(file).map_err(|e| format!("Cannot stat '{}': {}", file, e))?;
+    let size = metadata.len();
+    let mtime_ns = metadata.modified()?.duration_since(UNIX_EPOCH).map(|mtime| mtime.as_nanos()).unwrap_or(0);
+    let key = path::absolute(file)?.to_string_lossy().into_owned();
+
+    let (mut state, mut offset, start) = match read_journal(journal_path)? {
+        None => (State::new(algorithm), 0, Start::Fresh),
+        Some(journal) => {
+            let state = (journal.path == key && journal.size == size && journal.mtime_ns == mtime_ns && journal.algorithm == algorithm && journal.offset <= size)
+                .then(|| State::decode(algorithm, &journal.state))
+                .flatten();
+            match state {
+                Some(state) => (state, journal.offset, Start::Resumed { offset: journal.offset }),
+                None => (State::new(algorithm), 0, Start::Stale),
+            }
+        }
+    };
+
+    let mut input = File::open(file).map_err(|e| format!("Cannot open '{}': {}", file, e))?;
+    input.seek(SeekFrom::Start(offset))?;
+
+    let checkpoint = |state: &State, offset: u64| {
+        let journal = Journal { path: key.clone(), size, mtime_ns, algorithm: algorithm.to_string(), offset, state: state.encode() };
+        write_journal(journal_path, &journal)
+    };
+
+    let _guard = interrupt::catch();
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut last_checkpoint = offset;
+    loop {
+        let read = read_full(&mut input, &mut buffer)?;
+        if read < CHUNK_SIZE {
+            let digest = state.finalize(&buffer[..read], offset + read as u64);
+            match fs::remove_file(journal_path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(format!("Cannot remove journal '{}': {}", journal_path, e).into());
+                }
+                _ => {}
+            }
+            return Ok((digest, start));
+        }
+
+        state.update(&buffer);
+        offset += read as u64;
+
+        if interrupt::interrupted() {
+            checkpoint(&state, offset)?;
+            return Err(format!("interrupted at byte {} of {}; run again with the same --journal to resume", offset, size).into());
+        }
+        if offset - last_checkpoint >= interval {
+            checkpoint(&state, offset)?;
+            last_checkpoint = offset;
+        }
+    }
+}
+
+fn read_journal(journal_path: &str) -> Result<Option<Journal>, Box<dyn std::error::Error>> {
+    if !Path::new(journal_path).exists() {
+        return Ok(None);
+    }
+
+    let contents = fs::read_to_string(journal_path)
+        .map_err(|e| format!("Cannot read journal '{}': {}", journal_path, e))?;
+    serde_json::from_str(&contents)
+        .map(Some)
+        .map_err(|e| format!("Journal '{}' is not valid; delete it to start over: {}", journal_path, e).into())
+}
+
+/// Writes through a temporary file and renames it over the journal, so a
+/// crash mid-write leaves the previous checkpoint intact.
+fn write_journal(journal_path: &str, journal: &Journal) -> Result<(), Box<dyn std::error::Error>> {
+    let contents = serde_json::to_string(journal)?;
+    let temporary = format!("{}.tmp", journal_path);
+    fs::write(&temporary, contents + "\n")
+        .and_then(|()| fs::rename(&temporary, journal_path))
+        .map_err(|e| format!("Cannot write journal '{}': {}", journal_path, e).into())
+}
@@ -0,0 +1,167 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fs;
+use dialoguer::Input;
+use serde_json::Value;
+
+use crate::algorithms::{select_algorithm, AlgorithmSpec};
+use crate::audit;
+
+/// Longest value shown in a difference before it is cut short.
+const MAX_SHOWN: usize = 60;
+
+/// A JSON document parsed and in canonical form: object keys sorted and
+/// insignificant whitespace dropped, as `serde_json` writes it. Two
+/// documents with the same data have the same canonical text however they
+/// were formatted or their keys ordered.
+pub struct CanonicalJson {
+    pub value: Value,
+    pub text: String,
+}
+
+pub fn canonical_json(path: &str) -> Result<CanonicalJson, Box<dyn std::error::Error>> {
+    let data = fs::read(path).map_err(|e| format!("Cannot read '{}': {}", path, e))?;
+    let value: Value = serde_json::from_slice(&data).map_err(|e| format!("'{}' is not valid JSON: {}", path, e))?;
+    let text = serde_json::to_string(&value)?;
+    Ok(CanonicalJson { value, text })
+}
+
+/// How a path differs between the first document and the second.
+pub enum Difference {
+    Added(Value),
+    Removed(Value),
+    Changed(Value, Value),
+}
+
+/// Paths, as JSON Pointers, where two documents differ. Objects are compared
+/// key by key and arrays index by index, descending into values present on
+/// both sides; anything else that differs, including a value whose type
+/// changed, is reported whole. An element inserted into an array shows as
+/// every later index changed and one added at the end, since indexes carry
+/// no identity.
+pub fn diff(first: &Value, second: &Value) -> Vec<(String, Difference)> {
+    let mut differences = Vec::new();
+    diff_at(String::new(), first, second, &mut differences);
+    differences
+}
+
+fn diff_at(path: String, first: &Value, second: &Value, differences: &mut Vec<(String, Difference)>) {
+    match (first, second) {
+        (Value::Object(first), Value::Object(second)) => {
+            let keys: BTreeSet<&String> = first.keys().chain(second.keys()).collect();
+            for key in keys {
+                let path = format!("{}/{}", path, escape_pointer(key));
+                match (first.get(key), second.get(key)) {
+                    (Some(value), Some(other)) => diff_at(path, value, other, differences),
+                    (Some(value), None) => differences.push((path, Difference::Removed(value.clone()))),
+                    (None, Some(other)) => differences.push((path, Difference::Added(other.clone()))),
+                    (None, None) => unreachable!(),
+                }
+            }
+        }
+        (Value::Array(first), Value::Array(second)) => {
+            for index in 0..first.len().max(second.len()) {
+                let path = format!("{}/{}", path, index);
+                match (first.get(index), second.get(index)) {
+                    (Some(value), Some(other)) => diff_at(path, value, other, differences),
+                    (Some(value), None) => differences.push((path, Difference::Removed(value.clone()))),
+                    (None, Some(other)) => differences.push((path, Difference::Added(other.clone()))),
+                    (None, None) => unreachable!(),
+                }
+            }
+        }
+        (first, second) if first != second => differences.push((path, Difference::Changed(first.clone(), second.clone()))),
+        _ => {}
+    }
+}
+
+/// JSON Pointer escaping (RFC 6901): `~` as `~0` and `/` as `~1`.
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// The top-level key or index a pointer starts with, unescaped; `None` for
+/// the root itself.
+fn top_level(path: &str) -> Option<String> {
+    let first = path.strip_prefix('/')?.split('/').next()?;
+    Some(first.replace("~1", "/").replace("~0", "~"))
+}
+
+/// A value as compact JSON, cut short past `MAX_SHOWN` characters.
+struct Shown<'a>(&'a Value);
+
+impl fmt::Display for Shown<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = self.0.to_string();
+        match text.char_indices().nth(MAX_SHOWN) {
+            Some((end, _)) => write!(f, "{}...", &text[..end]),
+            None => write!(f, "{}", text),
+        }
+    }
+}
+
+/// One line per difference, e.g. `changed /port: 80 -> 8080`.
+pub fn describe(path: &str, difference: &Difference) -> String {
+    let path = if path.is_empty() { "(root)" } else { path };
+    match difference {
+        Difference::Added(value) => format!("added   {}: {}", path, Shown(value)),
+        Difference::Removed(value) => format!("removed {}: {}", path, Shown(value)),
+        Difference::Changed(first, second) => format!("changed {}: {} -> {}", path, Shown(first), Shown(second)),
+    }
+}
+
+/// Compares two JSON documents by the digests of their canonical forms and,
+/// when the documents differ, lists the paths that changed. Returns whether they
+/// matched.
+///
+/// Numbers compare as `serde_json` parses them: integers and floats stay
+/// distinct, so `1` and `1.0` differ while `1.50` and `1.5` match, and
+/// duplicate object keys keep only their last value.
+pub fn compare_json(path1: &str, path2: &str, spec: AlgorithmSpec) -> Result<bool, Box<dyn std::error::Error>> {
+    let first = canonical_json(path1)?;
+    let second = canonical_json(path2)?;
+    let hash1 = spec.hash_bytes(first.text.as_bytes())?;
+    let hash2 = spec.hash_bytes(second.text.as_bytes())?;
+    audit::record(&spec.to_string(), &format!("{} (canonical JSON)", path1), &hash1);
+    audit::record(&spec.to_string(), &format!("{} (canonical JSON)", path2), &hash2);
+
+    println!("{}  {}", hash1, path1);
+    println!("{}  {}", hash2, path2);
+    if first.text == second.text {
+        println!("MATCH: the documents hold the same JSON data.");
+        return Ok(true);
+    }
+    if hash1 == hash2 {
+        println!("The {} digests match, but the documents differ: a collision!", spec);
+    }
+
+    let differences = diff(&first.value, &second.value);
+    let mut keys: Vec<String> = differences.iter().filter_map(|(path, _)| top_level(path)).collect();
+    keys.dedup();
+    println!("DIFFERENT: {} paths differ", differences.len());
+    if !keys.is_empty() {
+        println!("Top-level keys: {}", keys.join(", "));
+    }
+    for (path, difference) in &differences {
+        println!("  {}", describe(path, difference));
+    }
+    Ok(false)
+}
+
+pub fn compare_json_interactive() {
+    let path1: String = Input::new()
+        .with_prompt("Enter first JSON file path")
+        .interact_text()
+        .unwrap();
+    let path2: String = Input::new()
+        .with_prompt("Enter second JSON file path")
+        .interact_text()
+        .unwrap();
+    let spec = AlgorithmSpec::plain(select_algorithm());
+
+    println!("\nCanonical JSON, {} digests:", spec);
+    if let Err(e) = compare_json(path1.trim(), path2.trim(), spec) {
+        eprintln!("Error: {}", e);
+    }
+    println!();
+}
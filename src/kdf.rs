@@ -0,0 +1,88 @@
+use dialoguer::{Input, Select};
+use hex::encode;
+use hkdf::Hkdf;
+use sha2::{Sha256, Sha512};
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::input::{read_bytes, read_secret_bytes};
+
+pub fn key_derivation() {
+    let kdf_choices = vec!["HKDF (RFC 5869)"];
+    let kdf_selection = Select::new()
+        .with_prompt("Choose a key derivation mode")
+        .items(&kdf_choices)
+        .default(0)
+        .interact()
+        .unwrap();
+
+    match kdf_selection {
+        0 => hkdf_interactive(),
+        _ => unreachable!(),
+    }
+}
+
+/// HKDF in its two stages: extract condenses the input key material into a
+/// pseudorandom key (HMAC(salt, IKM)), expand stretches that key into as
+/// many output bytes as needed, bound to the `info` context string.
+///
+/// The input key material, the PRK and the OKM are held in `Zeroizing`
+/// buffers and wiped once printed. The copy of the PRK inside the `Hkdf`
+/// value is not, since the `hkdf` crate offers no way to clear it; salt and
+/// info are not secret and are kept in plain buffers.
+fn hkdf_interactive() {
+    let hash_choices = vec!["HKDF-SHA256", "HKDF-SHA512"];
+    let hash_selection = Select::new()
+        .with_prompt("Choose the underlying hash")
+        .items(&hash_choices)
+        .default(0)
+        .interact()
+        .unwrap();
+
+    let ikm = read_secret_bytes("Input key material (text, or 0x-prefixed hex)");
+    let salt = read_bytes("Salt (text, 0x-prefixed hex, or empty for none)", true);
+    let info = read_bytes("Info / context string (may be empty)", true);
+
+    // RFC 5869 caps the output at 255 hash blocks.
+    let max_len = match hash_selection {
+        0 => 255 * 32,
+        1 => 255 * 64,
+        _ => unreachable!(),
+    };
+    let length: usize = Input::new()
+        .with_prompt(format!("Output length in bytes (1-{})", max_len))
+        .default(32)
+        .validate_with(|len: &usize| {
+            if (1..=max_len).contains(len) { Ok(()) } else { Err(format!("must be between 1 and {}", max_len)) }
+        })
+        .interact_text()
+        .unwrap();
+
+    let salt = if salt.is_empty() { None } else { Some(salt.as_slice()) };
+    let mut okm = Zeroizing::new(vec![0u8; length]);
+    let prk = match hash_selection {
+        0 => {
+            let (mut prk, hkdf) = Hkdf::<Sha256>::extract(salt, &ikm);
+            hkdf.expand(&info, &mut okm).unwrap();
+            let copy = Zeroizing::new(prk.to_vec());
+            prk.as_mut_slice().zeroize();
+            copy
+        }
+        1 => {
+            let (mut prk, hkdf) = Hkdf::<Sha512>::extract(salt, &ikm);
+            hkdf.expand(&info, &mut okm).unwrap();
+            let copy = Zeroizing::new(prk.to_vec());
+            prk.as_mut_slice().zeroize();
+            copy
+        }
+        _ => unreachable!(),
+    };
+
+    println!("\nAlgorithm: {}", hash_choices[hash_selection]);
+    println!("Salt: {}", salt.map(encode).unwrap_or_else(|| "(none: a block of zeros is used)".to_string()));
+    println!("Info: {}", encode(&info));
+    println!();
+    println!("Extract -> PRK: {}", encode(&*prk));
+    println!("Expand  -> OKM: {}", encode(&*okm));
+    println!();
+    println!("HKDF is what TLS 1.3 and Signal use to turn a shared secret into keys.\n");
+}
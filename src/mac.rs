@@ -0,0 +1,471 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::time::{SystemTime, UNIX_EPOCH};
+use dialoguer::{Input, Select};
+use hex::encode;
+use indicatif::{ProgressBar, ProgressStyle};
+use hmac::{Hmac, Mac};
+use sha2::{Sha256, Sha512};
+use zeroize::Zeroizing;
+
+use crate::algorithms::{check_file, digest_bytes, read_file, select_algorithm};
+use crate::input::{read_secret_bytes, read_text_or_file};
+use crate::interrupt;
+
+const MAC_CHOICES: &[&str] = &["HMAC-SHA256", "HMAC-SHA512"];
+
+/// Hex digits kept from each token digest: 64 bits is plenty for a token
+/// that is only valid for one window.
+const TOKEN_HEX_LEN: usize = 16;
+
+/// Read size for streamed HMACs; memory use stays at this whatever the
+/// input's length.
+const STREAM_CHUNK: usize = 64 * 1024;
+
+pub fn message_authentication() {
+    let mac_choices = vec![
+        "Seal HMAC Envelope (message || mac)",
+        "Open HMAC Envelope",
+        "Generate Time-Window Tokens",
+        "Verify Time-Window Token",
+        "Streaming HMAC (File or Stdin)",
+    ];
+    let mac_selection = Select::new()
+        .with_prompt("Choose a message authentication mode")
+        .items(&mac_choices)
+        .default(0)
+        .interact()
+        .unwrap();
+
+    match mac_selection {
+        0 => seal_envelope(),
+        1 => open_envelope(),
+        2 => generate_tokens(),
+        3 => verify_token(),
+        4 => streaming_hmac(),
+        _ => unreachable!(),
+    }
+}
+
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).unwrap();
+    mac.update(message);
+    mac.finalize().into_bytes().into()
+}
+
+/// `HMAC(key, message)` with the hash at `MAC_CHOICES[hash]`.
+fn hmac(hash: usize, key: &[u8], message: &[u8]) -> Vec<u8> {
+    match hash {
+        0 => hmac_sha256(key, message).to_vec(),
+        1 => {
+            let mut mac = Hmac::<Sha512>::new_from_slice(key).unwrap();
+            mac.update(message);
+            mac.finalize().into_bytes().to_vec()
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// An HMAC being fed in pieces, for inputs too large to hold in memory.
+enum StreamMac {
+    Sha256(Hmac<Sha256>),
+    Sha512(Hmac<Sha512>),
+}
+
+impl StreamMac {
+    /// HMAC accepts keys of any length, so this cannot fail.
+    fn new(hash: usize, key: &[u8]) -> Self {
+        match hash {
+            0 => StreamMac::Sha256(Hmac::new_from_slice(key).unwrap()),
+            1 => StreamMac::Sha512(Hmac::new_from_slice(key).unwrap()),
+            _ => unreachable!(),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            StreamMac::Sha256(mac) => mac.update(data),
+            StreamMac::Sha512(mac) => mac.update(data),
+        }
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        match self {
+            StreamMac::Sha256(mac) => mac.finalize().into_bytes().to_vec(),
+            StreamMac::Sha512(mac) => mac.finalize().into_bytes().to_vec(),
+        }
+    }
+}
+
+/// `HMAC(key, everything read from reader)`, read in `STREAM_CHUNK` pieces
+/// and ticking `progress` by the bytes read. Returns `None` if Ctrl-C
+/// stopped it first.
+fn hmac_reader(hash: usize, key: &[u8], mut reader: impl Read, progress: &ProgressBar) -> io::Result<Option<Vec<u8>>> {
+    let mut mac = StreamMac::new(hash, key);
+    let mut buffer = vec![0u8; STREAM_CHUNK];
+    let _guard = interrupt::catch();
+
+    loop {
+        if interrupt::interrupted() {
+            return Ok(None);
+        }
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        mac.update(&buffer[..read]);
+        progress.inc(read as u64);
+    }
+    Ok(Some(mac.finalize()))
+}
+
+fn mac_len(hash: usize) -> usize {
+    match hash {
+        0 => 32,
+        1 => 64,
+        _ => unreachable!(),
+    }
+}
+
+/// Checks `tag` against `HMAC(key, message)` in constant time. `verify_slice`
+/// compares every byte whatever the first mismatch, so the time taken leaks
+/// nothing about how much of a forged tag was right; comparing with `==` can
+/// return at the first differing byte and lets an attacker recover a valid
+/// tag one byte at a time.
+fn verify_hmac(hash: usize, key: &[u8], message: &[u8], tag: &[u8]) -> bool {
+    match hash {
+        0 => {
+            let mut mac = Hmac::<Sha256>::new_from_slice(key).unwrap();
+            mac.update(message);
+            mac.verify_slice(tag).is_ok()
+        }
+        1 => {
+            let mut mac = Hmac::<Sha512>::new_from_slice(key).unwrap();
+            mac.update(message);
+            mac.verify_slice(tag).is_ok()
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn select_mac() -> usize {
+    Select::new()
+        .with_prompt("Choose the MAC")
+        .items(MAC_CHOICES)
+        .default(0)
+        .interact()
+        .unwrap()
+}
+
+/// The authenticated envelope `message || HMAC(key, message)`: the message
+/// travels in the clear with a fixed-length tag attached, as in JWT-style
+/// tokens and signed cookies. It authenticates, it does not encrypt.
+fn seal_envelope() {
+    let (label, message) = match read_text_or_file() {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return;
+        }
+    };
+    let hash = select_mac();
+    let key = read_secret_bytes("Key (text, or 0x-prefixed hex)");
+
+    let tag = hmac(hash, &key, &message);
+    let mut envelope = message;
+    envelope.extend_from_slice(&tag);
+
+    println!("\nInput: {}", label);
+    println!("MAC: {}", MAC_CHOICES[hash]);
+    println!("Tag:      {}", encode(&tag));
+    println!("Envelope: {} bytes (message {} + tag {})", envelope.len(), envelope.len() - tag.len(), tag.len());
+    println!("  {}\n", encode(&envelope));
+    println!("Anyone can read the message; only holders of the key can make a tag that opens.\n");
+}
+
+/// Splits an envelope into message and trailing tag, recomputes the MAC over
+/// the message and checks the tag in constant time.
+fn open_envelope() {
+    let source_choices = vec!["Hex", "File"];
+    let source = Select::new()
+        .with_prompt("Envelope source")
+        .items(&source_choices)
+        .default(0)
+        .interact()
+        .unwrap();
+
+    let envelope = match source {
+        0 => {
+            let hex_input: String = Input::new()
+                .with_prompt("Envelope bytes in hex")
+                .interact_text()
+                .unwrap();
+            let hex_input: String = hex_input.split_whitespace().collect();
+            hex::decode(hex_input.strip_prefix("0x").unwrap_or(&hex_input)).map_err(|e| format!("invalid hex: {}", e).into())
+        }
+        1 => {
+            let path: String = Input::new()
+                .with_prompt("Enter envelope file path")
+                .interact_text()
+                .unwrap();
+            read_file(&path)
+        }
+        _ => unreachable!(),
+    };
+    let envelope = match envelope {
+        Ok(envelope) => envelope,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return;
+        }
+    };
+
+    let hash = select_mac();
+    if envelope.len() < mac_len(hash) {
+        eprintln!("Error: {} bytes is too short to hold a {}-byte {} tag", envelope.len(), mac_len(hash), MAC_CHOICES[hash]);
+        return;
+    }
+    let key = read_secret_bytes("Key (text, or 0x-prefixed hex)");
+
+    let (message, tag) = envelope.split_at(envelope.len() - mac_len(hash));
+    println!("\nMAC: {}", MAC_CHOICES[hash]);
+    println!("Message: {} bytes", message.len());
+    if let Ok(text) = std::str::from_utf8(message) {
+        println!("  '{}'", text);
+    }
+    println!("Tag: {}", encode(tag));
+
+    if verify_hmac(hash, &key, message, tag) {
+        println!("AUTHENTIC: the tag matches the message under this key.\n");
+    } else {
+        println!("REJECTED: the message, the tag or the key differs from what was sealed.\n");
+    }
+}
+
+/// HMAC of a file or of stdin, read in chunks with a progress bar instead
+/// of loaded whole, so multi-gigabyte inputs can be authenticated. Gives the
+/// same tag as hashing the input in one piece.
+fn streaming_hmac() {
+    let source_choices = vec!["File", "Stdin (until end of input)"];
+    let source = Select::new()
+        .with_prompt("Input source")
+        .items(&source_choices)
+        .default(0)
+        .interact()
+        .unwrap();
+    let path = match source {
+        0 => {
+            let path: String = Input::new()
+                .with_prompt("Enter file path")
+                .interact_text()
+                .unwrap();
+            if let Err(e) = check_file(&path) {
+                eprintln!("Error: {}", e);
+                return;
+            }
+            Some(path)
+        }
+        _ => None,
+    };
+    let hash = select_mac();
+    let key = read_secret_bytes("Key (text, or 0x-prefixed hex)");
+
+    let result = match &path {
+        Some(path) => File::open(path).and_then(|file| {
+            let progress = ProgressBar::new(file.metadata()?.len());
+            progress.set_style(
+                ProgressStyle::with_template("{bar:40} {bytes}/{total_bytes} ({binary_bytes_per_sec}, {eta} left)").unwrap(),
+            );
+            let tag = hmac_reader(hash, &key, file, &progress);
+            progress.finish_and_clear();
+            tag
+        }),
+        None => {
+            // The length of a stream isn't known up front, so only count.
+            let progress = ProgressBar::new_spinner();
+            progress.set_style(ProgressStyle::with_template("{spinner} {bytes} read ({binary_bytes_per_sec})").unwrap());
+            let tag = hmac_reader(hash, &key, io::stdin().lock(), &progress);
+            progress.finish_and_clear();
+            tag
+        }
+    };
+    let label = match &path {
+        Some(path) => format!("'{}'", path),
+        None => "stdin".to_string(),
+    };
+
+    match result {
+        Ok(Some(tag)) => {
+            println!("\nInput: {}", label);
+            println!("MAC: {}", MAC_CHOICES[hash]);
+            println!("Tag: {}\n", encode(tag));
+        }
+        Ok(None) => eprintln!("Interrupted; no tag computed."),
+        Err(e) => eprintln!("Error: reading {}: {}", label, e),
+    }
+}
+
+/// The secret, window size and per-window token count shared by both sides
+/// of the token schedule.
+struct TokenSchedule {
+    secret: Zeroizing<Vec<u8>>,
+    algorithm: &'static str,
+    window_secs: u64,
+    tokens: u32,
+}
+
+impl TokenSchedule {
+    fn prompt() -> TokenSchedule {
+        let secret = read_secret_bytes("Shared secret (text, or 0x-prefixed hex)");
+        let algorithm = select_algorithm();
+        let window_secs: u64 = Input::new()
+            .with_prompt("Window size in seconds")
+            .default(60)
+            .validate_with(|secs: &u64| if *secs > 0 { Ok(()) } else { Err("must be at least 1") })
+            .interact_text()
+            .unwrap();
+        let tokens: u32 = Input::new()
+            .with_prompt("Tokens per window")
+            .default(5)
+            .validate_with(|count: &u32| if *count > 0 { Ok(()) } else { Err("must be at least 1") })
+            .interact_text()
+            .unwrap();
+        TokenSchedule { secret, algorithm, window_secs, tokens }
+    }
+
+    fn current_window(&self) -> u64 {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        now / self.window_secs
+    }
+
+    /// Token `counter` of `window`: the digest of
+    /// `secret || window (u64 BE) || counter (u32 BE)`, truncated to
+    /// `TOKEN_HEX_LEN` hex digits. Either side can compute the schedule from
+    /// the secret and the clock alone, with no token list to store or sync.
+    fn token(&self, window: u64, counter: u32) -> Result<String, String> {
+        let mut data = Zeroizing::new(self.secret.to_vec());
+        data.extend_from_slice(&window.to_be_bytes());
+        data.extend_from_slice(&counter.to_be_bytes());
+        let digest = encode(digest_bytes(&data, self.algorithm)?);
+        Ok(digest[..TOKEN_HEX_LEN.min(digest.len())].to_string())
+    }
+}
+
+/// A deterministic schedule of single-use tokens, in the style of the
+/// nonces a client spends against a rate limit: each window of time has a
+/// fixed number of tokens, and using one more than that means waiting for
+/// the next window.
+///
+/// Plain `secret || data` hashing is used here to keep the schedule visible;
+/// with SHA-256 or Keccak-256 a real deployment should use HMAC instead, see
+/// the length-extension demo.
+fn generate_tokens() {
+    let schedule = TokenSchedule::prompt();
+    let window = schedule.current_window();
+    let start = window * schedule.window_secs;
+
+    println!("\nAlgorithm: {}", schedule.algorithm);
+    println!("Window: {} ({}s, started at unix time {}, {}s left)", window, schedule.window_secs, start, start + schedule.window_secs - SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs());
+    println!();
+    for counter in 0..schedule.tokens {
+        match schedule.token(window, counter) {
+            Ok(token) => println!("  {:>3}. {}", counter, token),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return;
+            }
+        }
+    }
+    println!("\nEach token is valid once, and only during this window.\n");
+}
+
+/// Checks a presented token against every token of the current window, and
+/// of the previous one to allow for clock skew at the boundary. Every
+/// candidate is compared, in constant time, so the time taken does not give
+/// away which counter matched or how close a guess came.
+fn verify_token() {
+    let schedule = TokenSchedule::prompt();
+    let presented: String = Input::new()
+        .with_prompt("Presented token")
+        .interact_text()
+        .unwrap();
+    let presented = presented.trim().to_lowercase();
+
+    let window = schedule.current_window();
+    let mut matched = None;
+    for candidate_window in [window, window.saturating_sub(1)] {
+        for counter in 0..schedule.tokens {
+            let token = match schedule.token(candidate_window, counter) {
+                Ok(token) => token,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    return;
+                }
+            };
+            if constant_time_eq(token.as_bytes(), presented.as_bytes()) && matched.is_none() {
+                matched = Some((candidate_window, counter));
+            }
+        }
+    }
+
+    println!("\nAlgorithm: {}", schedule.algorithm);
+    println!("Current window: {}", window);
+    match matched {
+        Some((matched_window, counter)) if matched_window == window => {
+            println!("VALID: token {} of the current window.\n", counter);
+        }
+        Some((_, counter)) => {
+            println!("VALID: token {} of the previous window, accepted for clock skew.\n", counter);
+        }
+        None => println!("REJECTED: not a token of the current or previous window.\n"),
+    }
+    println!("A real server also records spent tokens so each one works only once.\n");
+}
+
+/// Compares without stopping at the first difference. The length itself is
+/// not secret here, so unequal lengths fail straight away.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A reader that hands out at most `step` bytes per read, as a pipe or
+    /// socket might.
+    struct Trickle<'a> {
+        data: &'a [u8],
+        step: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+            let len = self.step.min(self.data.len()).min(buffer.len());
+            buffer[..len].copy_from_slice(&self.data[..len]);
+            self.data = &self.data[len..];
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn streamed_hmac_matches_one_shot() {
+        let key = b"streaming key";
+        let data: Vec<u8> = (0..3 * STREAM_CHUNK + 17).map(|i| (i * 31 % 251) as u8).collect();
+
+        for (hash, name) in MAC_CHOICES.iter().enumerate() {
+            let expected = hmac(hash, key, &data);
+            for step in [1, 63, 64, 65, 1000, STREAM_CHUNK, STREAM_CHUNK + 1, data.len()] {
+                let mut mac = StreamMac::new(hash, key);
+                data.chunks(step).for_each(|chunk| mac.update(chunk));
+                assert_eq!(mac.finalize(), expected, "{} fed {} bytes at a time", name, step);
+
+                let reader = Trickle { data: &data, step };
+                let tag = hmac_reader(hash, key, reader, &ProgressBar::hidden()).unwrap();
+                assert_eq!(tag, Some(expected.clone()), "{} read {} bytes at a time", name, step);
+            }
+        }
+    }
+}
@@ -1,80 +1,219 @@
-use std::io::{self, Write};
-use std::fs;
-use std::path::Path;
-use sha2::{Sha256, Digest as _};
-use blake2::Blake2b512;
-use md5::compute;
-use tiny_keccak::{Hasher, Keccak};
-use dialoguer::Select;
-use hex::encode;
-
-fn hash_text(input: &str, algorithm: &str) -> String {
-    match algorithm {
-        "SHA-256" => {
-            let mut hasher = Sha256::new();
-            hasher.update(input.as_bytes());
-            encode(hasher.finalize())
-        }
-        "Keccak-256" => {
-            let mut keccak = Keccak::v256();
-            let mut output = [0u8; 32];
-            keccak.update(input.as_bytes());
-            keccak.finalize(&mut output);
-            encode(output)
-        }
-        "Blake2b" => {
-            let mut hasher = Blake2b512::new();
-            hasher.update(input.as_bytes());
-            encode(hasher.finalize())
-        }
-        "MD5" => {
-            encode(compute(input.as_bytes()).0)
+mod advanced;
+mod algorithms;
+mod analysis;
+mod archive;
+mod audit;
+mod baseline;
+mod bench;
+mod cache;
+mod cbor;
+mod cli;
+mod dataset;
+mod dedup;
+#[cfg(feature = "pdf")]
+mod document;
+mod editor;
+mod ethereum;
+mod fixtures;
+mod follow;
+mod fuzz;
+#[cfg(feature = "identicon")]
+mod identicon;
+mod input;
+mod interrupt;
+mod journal;
+mod jsondiff;
+mod kdf;
+mod mac;
+mod manifest;
+mod merkle;
+mod metrics;
+mod output;
+#[cfg(feature = "plugins")]
+mod plugin;
+mod priority;
+#[cfg(all(target_os = "linux", feature = "procmem"))]
+mod procmem;
+mod records;
+#[cfg(feature = "remote")]
+mod remote;
+mod retrievability;
+mod salt;
+mod scrub;
+#[cfg(feature = "serve")]
+mod serve;
+mod siphash;
+mod source;
+#[cfg(target_os = "linux")]
+mod sparse;
+mod tabular;
+mod teaching;
+mod torrent;
+mod transform;
+mod treehash;
+mod uuidv5;
+mod vectors;
+mod verify;
+
+use std::io::{self, Read, Write};
+use clap::Parser;
+use dialoguer::{Input, Select};
+
+use algorithms::{hash_bytes, hash_file, hash_text, read_file, select_algorithm};
+
+/// Number of positions at which two hex digests differ.
+fn count_differences(hash1: &str, hash2: &str) -> usize {
+    hash1.chars().zip(hash2.chars())
+        .filter(|(a, b)| a != b)
+        .count()
+}
+
+/// Length in bits of the common leading prefix of two hex digests, which
+/// may come from different algorithms and have different lengths.
+fn common_prefix_bits(hash1: &str, hash2: &str) -> usize {
+    let mut bits = 0;
+    for (a, b) in hash1.chars().zip(hash2.chars()) {
+        let diff = a.to_digit(16).unwrap() ^ b.to_digit(16).unwrap();
+        if diff != 0 {
+            return bits + (diff.leading_zeros() - 28) as usize;
         }
-        _ => unreachable!(),
+        bits += 4;
     }
+    bits
 }
 
-fn hash_file(file_path: &str, algorithm: &str) -> Result<String, Box<dyn std::error::Error>> {
-    let path = Path::new(file_path);
+/// Compares two already-computed digests by their shared leading bits,
+/// without hashing anything.
+fn compare_digest_prefixes() {
+    let read_digest = |prompt: &str| -> String {
+        Input::new()
+            .with_prompt(prompt)
+            .validate_with(|digest: &String| {
+                let digest = digest.strip_prefix("0x").unwrap_or(digest);
+                if !digest.is_empty() && digest.chars().all(|c| c.is_ascii_hexdigit()) {
+                    Ok(())
+                } else {
+                    Err("not a hex digest")
+                }
+            })
+            .interact_text()
+            .unwrap()
+    };
 
-    if !path.exists() {
-        return Err(format!("File '{}' does not exist", file_path).into());
-    }
+    let digest1 = read_digest("Enter first hex digest");
+    let digest2 = read_digest("Enter second hex digest");
+    let digest1 = digest1.strip_prefix("0x").unwrap_or(&digest1).to_ascii_lowercase();
+    let digest2 = digest2.strip_prefix("0x").unwrap_or(&digest2).to_ascii_lowercase();
+
+    let bits = common_prefix_bits(&digest1, &digest2);
+    let compared = digest1.len().min(digest2.len()) * 4;
 
-    if !path.is_file() {
-        return Err(format!("'{}' is not a file", file_path).into());
+    println!("\nDigest 1: {} ({} bits)", digest1, digest1.len() * 4);
+    println!("Digest 2: {} ({} bits)", digest2, digest2.len() * 4);
+    println!();
+    println!("Common prefix: {} of {} compared bits", bits, compared);
+    if bits < compared {
+        // Two unrelated digests share at least n leading bits with probability 2^-n.
+        println!("Chance for unrelated digests to share at least this much: 1 in 2^{}\n", bits);
+    } else {
+        println!("The shorter digest is a prefix of the longer one.\n");
     }
+}
 
-    let file_content = fs::read(file_path)?;
+/// Decides whether two files are byte-identical in stages, stopping at the
+/// first that settles it: sizes, then digests, then (unless skipped) a full
+/// byte comparison to rule out a collision.
+fn files_identical() {
+    let path1: String = Input::new()
+        .with_prompt("Enter first file path")
+        .interact_text()
+        .unwrap();
+    let path2: String = Input::new()
+        .with_prompt("Enter second file path")
+        .interact_text()
+        .unwrap();
 
-    Ok(match algorithm {
-        "SHA-256" => {
-            let mut hasher = Sha256::new();
-            hasher.update(&file_content);
-            encode(hasher.finalize())
+    let algorithm = select_algorithm();
+    let confirm_choices = vec!["Yes", "No, trust a matching hash"];
+    let confirm_bytes = Select::new()
+        .with_prompt("Confirm a hash match byte by byte?")
+        .items(&confirm_choices)
+        .default(0)
+        .interact()
+        .unwrap()
+        == 0;
+
+    let (size1, size2) = match (std::fs::metadata(&path1), std::fs::metadata(&path2)) {
+        (Ok(meta1), Ok(meta2)) if meta1.is_file() && meta2.is_file() => (meta1.len(), meta2.len()),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("Error: {}", e);
+            return;
         }
-        "Keccak-256" => {
-            let mut keccak = Keccak::v256();
-            let mut output = [0u8; 32];
-            keccak.update(&file_content);
-            keccak.finalize(&mut output);
-            encode(output)
+        _ => {
+            eprintln!("Error: both paths must be regular files");
+            return;
         }
-        "Blake2b" => {
-            let mut hasher = Blake2b512::new();
-            hasher.update(&file_content);
-            encode(hasher.finalize())
+    };
+
+    println!("\nStage 1, sizes: {} vs {} bytes", size1, size2);
+    if size1 != size2 {
+        println!("DIFFERENT: the sizes differ.\n");
+        return;
+    }
+
+    let (hash1, hash2) = match (hash_file(&path1, algorithm), hash_file(&path2, algorithm)) {
+        (Ok(hash1), Ok(hash2)) => (hash1, hash2),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("Error: {}", e);
+            return;
+        }
+    };
+
+    println!("Stage 2, {} digests:", algorithm);
+    println!("  {}", hash1);
+    println!("  {}", hash2);
+    if hash1 != hash2 {
+        println!("DIFFERENT: the digests differ.\n");
+        return;
+    }
+
+    if !confirm_bytes {
+        println!("IDENTICAL by hash; byte comparison skipped.\n");
+        return;
+    }
+
+    println!("Stage 3, byte comparison:");
+    match first_difference(&path1, &path2) {
+        Ok(None) => println!("IDENTICAL: every byte matches.\n"),
+        Ok(Some(offset)) => println!("DIFFERENT at byte {}, despite matching digests: a {} collision!\n", offset, algorithm),
+        Err(e) => eprintln!("Error: {}", e),
+    }
+}
+
+/// Offset of the first differing byte, or `None` if the files are the same.
+fn first_difference(path1: &str, path2: &str) -> io::Result<Option<u64>> {
+    let mut file1 = io::BufReader::new(std::fs::File::open(path1)?);
+    let mut file2 = io::BufReader::new(std::fs::File::open(path2)?);
+    let mut buffer1 = [0u8; 64 * 1024];
+    let mut buffer2 = [0u8; 64 * 1024];
+    let mut offset = 0u64;
+
+    loop {
+        let read = file1.read(&mut buffer1)?;
+        if read == 0 {
+            return Ok(None);
         }
-        "MD5" => {
-            encode(compute(&file_content).0)
+        file2.read_exact(&mut buffer2[..read])?;
+        if let Some(index) = buffer1[..read].iter().zip(&buffer2[..read]).position(|(a, b)| a != b) {
+            return Ok(Some(offset + index as u64));
         }
-        _ => unreachable!(),
-    })
+        offset += read as u64;
+    }
 }
 
 fn compare_hashes() {
 
-    let compare_mode_choices = vec!["Compare Text", "Compare Files"];
+    let compare_mode_choices = vec!["Compare Text", "Compare Files", "Compare Digest Prefixes", "Files Identical?", "Compare JSON Documents"];
     let compare_mode = Select::new()
         .with_prompt("Choose comparison mode")
         .items(&compare_mode_choices)
@@ -82,6 +221,21 @@ fn compare_hashes() {
         .interact()
         .unwrap();
 
+    if compare_mode == 2 {
+        compare_digest_prefixes();
+        return;
+    }
+
+    if compare_mode == 3 {
+        files_identical();
+        return;
+    }
+
+    if compare_mode == 4 {
+        jsondiff::compare_json_interactive();
+        return;
+    }
+
     let (input1, input2, input_type) = match compare_mode {
         0 => {
             print!("Enter first text: ");
@@ -116,33 +270,31 @@ fn compare_hashes() {
         _ => unreachable!(),
     };
 
-    let choices = vec!["SHA-256", "Keccak-256", "Blake2b", "MD5"];
-    let selection = Select::new()
-        .with_prompt("Choose a hashing algorithm")
-        .items(&choices)
-        .default(0)
-        .interact()
-        .unwrap();
-
-    let algorithm = choices[selection];
+    let algorithm = select_algorithm();
+    let transform = transform::select_transform();
 
-    let hash1_result = match compare_mode {
-        0 => Ok(hash_text(&input1, algorithm)),
-        1 => hash_file(&input1, algorithm),
+    let hash_input = |input: &str| match (compare_mode, &transform) {
+        (0, None) => hash_text(input, algorithm).map_err(Into::into),
+        (1, None) => hash_file(input, algorithm),
+        (0, Some(transform)) => hash_bytes(&transform.apply(input.as_bytes().to_vec()), algorithm).map_err(Into::into),
+        (1, Some(transform)) => read_file(input).and_then(|data| hash_bytes(&transform.apply(data), algorithm).map_err(Into::into)),
         _ => unreachable!(),
     };
 
-    let hash2_result = match compare_mode {
-        0 => Ok(hash_text(&input2, algorithm)),
-        1 => hash_file(&input2, algorithm),
-        _ => unreachable!(),
-    };
+    let hash1_result = hash_input(&input1);
+    let hash2_result = hash_input(&input2);
 
     match (hash1_result, hash2_result) {
         (Ok(hash1), Ok(hash2)) => {
+            audit::record(algorithm, &audit_input(&input1, input_type), &hash1);
+            audit::record(algorithm, &audit_input(&input2, input_type), &hash2);
+
             println!("\nComparison Results:");
             println!("Algorithm: {}", algorithm);
             println!("Type: {}", input_type);
+            if let Some(transform) = &transform {
+                println!("Transform: {}", transform.name());
+            }
             println!();
             println!("Input 1: '{}'", input1);
             println!("Hash 1:  {}", hash1);
@@ -153,9 +305,7 @@ fn compare_hashes() {
 
             if hash1 == hash2 {
             } else {
-                let differences = hash1.chars().zip(hash2.chars())
-                    .filter(|(a, b)| a != b)
-                    .count();
+                let differences = count_differences(&hash1, &hash2);
                 let total_chars = hash1.len();
                 let difference_percentage = (differences as f64 / total_chars as f64) * 100.0;
 
@@ -171,11 +321,87 @@ fn compare_hashes() {
     }
 }
 
+fn read_version_byte() -> Option<u8> {
+    loop {
+        let version: String = Input::new()
+            .with_prompt("Version byte in hex (e.g. 00 for a Bitcoin address, empty for none)")
+            .allow_empty(true)
+            .interact_text()
+            .unwrap();
+
+        if version.is_empty() {
+            return None;
+        }
+        match u8::from_str_radix(&version, 16) {
+            Ok(byte) => return Some(byte),
+            Err(_) => eprintln!("Error: '{}' is not a single hex byte", version),
+        }
+    }
+}
+
+/// What an audit record names as the input: file paths as-is, but only the
+/// length of typed text so secrets don't end up in the log.
+fn audit_input(input: &str, input_type: &str) -> String {
+    match input_type {
+        "File" => input.to_string(),
+        _ => format!("text ({} bytes)", input.len()),
+    }
+}
+
+/// Mentions transient read errors that were retried, which would otherwise
+/// pass unnoticed when the retry succeeded.
+fn report_read_retries(quiet: bool) {
+    let retries = algorithms::take_read_retries();
+    if retries > 0 && !quiet {
+        eprintln!("Note: retried {} transient read error(s)", retries);
+    }
+}
+
 fn main() {
+    let args = cli::Cli::parse();
+    #[cfg(feature = "plugins")]
+    plugin::load(args.plugins.as_deref());
+    if args.syslog {
+        audit::enable();
+    }
+    if !args.menu_algos.is_empty() {
+        algorithms::set_menu_algorithms(&args.menu_algos);
+    }
+    algorithms::set_read_retries(args.retries);
+    if let Some(nice) = args.nice
+        && let Err(e) = priority::lower_priority(nice)
+    {
+        eprintln!("Warning: {}", e);
+    }
+    if args.is_non_interactive() {
+        let result = cli::run(&args);
+        report_read_retries(args.quiet);
+        if let Err(e) = result {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     println!("Hashing Function Demo");
 
     loop {
-        let mode_choices = vec!["Text Hashing", "File Hashing", "Compare Hashes"];
+        let mode_choices = vec![
+            "Text Hashing",
+            "File Hashing",
+            "Compare Hashes",
+            "Benchmarks",
+            "Verification",
+            "Manifests",
+            "Teaching Demos",
+            "Key Derivation",
+            "Archives",
+            "Advanced Algorithms",
+            "Analysis",
+            "Message Authentication",
+            "Edit and Hash ($EDITOR)",
+            "Ethereum",
+        ];
         let mode_selection = Select::new()
             .with_prompt("Choose hashing mode")
             .items(&mode_choices)
@@ -195,47 +421,197 @@ fn main() {
                         (input.to_string(), "Text")
                     }
                     1 => {
-                        print!("Enter file path to hash: ");
-                        io::stdout().flush().unwrap();
-                        let mut input = String::new();
-                        io::stdin().read_line(&mut input).unwrap();
-                        let input = input.trim();
-                        (input.to_string(), "File")
+                        let source_choices = vec!["Browse for the file", "Type the path"];
+                        let source = Select::new()
+                            .with_prompt("How do you want to choose the file?")
+                            .items(&source_choices)
+                            .default(0)
+                            .interact()
+                            .unwrap();
+                        let picked = if source == 0 { input::pick_file() } else { None };
+                        let input = match picked {
+                            Some(path) => path,
+                            None => {
+                                print!("Enter file path to hash: ");
+                                io::stdout().flush().unwrap();
+                                let mut input = String::new();
+                                io::stdin().read_line(&mut input).unwrap();
+                                input.trim().to_string()
+                            }
+                        };
+                        (input, "File")
                     }
                     _ => unreachable!(),
                 };
 
-                let choices = vec!["SHA-256", "Keccak-256", "Blake2b", "MD5"];
-                let selection = Select::new()
-                    .with_prompt("Choose a hashing algorithm")
-                    .items(&choices)
+                let algorithm = select_algorithm();
+
+                let little_endian = output::has_byte_order(algorithm) && {
+                    let order_choices = vec!["Big-endian (default display)", "Little-endian (memory order)"];
+                    Select::new()
+                        .with_prompt(format!("Byte order for the {} value", algorithm))
+                        .items(&order_choices)
+                        .default(0)
+                        .interact()
+                        .unwrap()
+                        == 1
+                };
+                let byte_order = |hash: String| if little_endian { output::reverse_bytes(&hash) } else { hash };
+
+                let format_selection = Select::new()
+                    .with_prompt("Choose an output format")
+                    .items(output::OUTPUT_FORMATS)
                     .default(0)
                     .interact()
                     .unwrap();
 
-                let algorithm = choices[selection];
-                let hash_result = match mode_selection {
-                    0 => {
-                        Ok(hash_text(&input, algorithm))
+                let base58_version = if format_selection == 4 {
+                    read_version_byte()
+                } else {
+                    None
+                };
+
+                let abbrev_len = if format_selection == 8 {
+                    Input::new()
+                        .with_prompt("Hex digits to keep")
+                        .default(output::DEFAULT_ABBREV)
+                        .validate_with(|len: &usize| if *len > 0 { Ok(()) } else { Err("must be at least 1") })
+                        .interact_text()
+                        .unwrap()
+                } else {
+                    output::DEFAULT_ABBREV
+                };
+
+                let integer_width = if format_selection == 9 {
+                    let width_choices = vec!["64-bit (first 8 bytes)", "128-bit (first 16 bytes)"];
+                    let width = Select::new()
+                        .with_prompt("Integer width")
+                        .items(&width_choices)
+                        .default(0)
+                        .interact()
+                        .unwrap();
+                    if width == 0 { 8 } else { 16 }
+                } else {
+                    8
+                };
+
+                let uuid_style = if format_selection == 12 {
+                    let style_choices = vec!["UUIDv8 (set version and variant bits)", "Raw (first 16 bytes as they are)"];
+                    let style = Select::new()
+                        .with_prompt("UUID style")
+                        .items(&style_choices)
+                        .default(0)
+                        .interact()
+                        .unwrap();
+                    if style == 0 { output::UuidStyle::V8 } else { output::UuidStyle::Raw }
+                } else {
+                    output::UuidStyle::V8
+                };
+
+                let transform = transform::select_transform();
+
+                let hash_result = match (mode_selection, &transform) {
+                    (0, None) => {
+                        hash_text(&input, algorithm).map_err(Into::into)
                     }
-                    1 => {
+                    (1, None) => {
                         hash_file(&input, algorithm)
                     }
+                    (0, Some(transform)) => {
+                        hash_bytes(&transform.apply(input.as_bytes().to_vec()), algorithm).map_err(Into::into)
+                    }
+                    (1, Some(transform)) => {
+                        read_file(&input).and_then(|data| hash_bytes(&transform.apply(data), algorithm).map_err(Into::into))
+                    }
                     _ => unreachable!(),
                 };
+                let hash_result = hash_result.map(byte_order);
+
+                if let Ok(hash) = &hash_result {
+                    audit::record(algorithm, &audit_input(&input, input_type), hash);
+                }
 
                 match hash_result {
+                    Ok(hash) if format_selection > 0 => {
+                        // Text input has no file name; checksum tools print `-` for stdin.
+                        let name = if mode_selection == 0 { "-" } else { input.as_str() };
+                        match format_selection {
+                            1 => println!("\n{}\n", output::format_coreutils(&hash, name)),
+                            2 => println!("\n{}\n", output::format_bsd(algorithm, &hash, name)),
+                            3 => println!("\n{}\n", output::format_base58(&hash)),
+                            4 => println!("\n{}\n", output::format_base58check(&hash, base58_version)),
+                            5 => {
+                                println!("\nFingerprint words: {}", output::format_bip39_words(&hash));
+                                println!("(a truncated fingerprint for reading aloud, not the full hash)\n");
+                            }
+                            6 => println!("\n{}\n", output::format_ipv6(&hash)),
+                            7 => {
+                                println!("\nEmoji fingerprint: {}", output::format_emoji(&hash));
+                                println!("(a truncated fingerprint for comparing by eye, not the full hash)\n");
+                            }
+                            11 => println!("\n{}\n", output::format_nix_base32(&hash)),
+                            12 => match output::format_uuid(&hash, uuid_style) {
+                                Ok(uuid) => {
+                                    println!("\n{}", uuid);
+                                    println!("(the first 128 bits as an identifier; not the full hash, and not for security)\n");
+                                }
+                                Err(e) => eprintln!("Error: {}", e),
+                            },
+                            #[cfg(feature = "identicon")]
+                            13 => {
+                                let path: String = Input::new()
+                                    .with_prompt("PNG file to write")
+                                    .default("identicon.png".to_string())
+                                    .interact_text()
+                                    .unwrap();
+                                match identicon::write_identicon(&hash, &path) {
+                                    Ok(()) => println!("\nWrote identicon for {} to '{}'\n", hash, path),
+                                    Err(e) => eprintln!("Error: {}", e),
+                                }
+                            }
+                            10 => {
+                                println!("\n{}", output::format_swatch(&hash, output::color_support()));
+                                println!("(a visual fingerprint of the first 192 bits, for a quick look only)\n");
+                            }
+                            9 => {
+                                println!("\n{}", output::format_integer(&hash, integer_width));
+                                println!("(the first {} bits as an integer, for bucketing only; not a secure hash)\n", integer_width.min(hash.len() / 2) * 8);
+                            }
+                            8 => {
+                                println!("\n{}", output::format_abbrev(&hash, abbrev_len));
+                                println!("(only {} bits; unlike the full hash, collisions are easy to find)\n", abbrev_len.min(hash.len()) * 4);
+                            }
+                            _ => unreachable!(),
+                        }
+                    }
                     Ok(hash) => {
                         println!("\nInput: '{}'", input);
                         println!("Type: {}", input_type);
                         println!("Algorithm: {}", algorithm);
+                        if let Some(transform) = &transform {
+                            let untransformed = match mode_selection {
+                                0 => hash_text(&input, algorithm).map_err(Into::into),
+                                _ => hash_file(&input, algorithm),
+                            };
+                            let input_len = match mode_selection {
+                                0 => input.len() as u64,
+                                _ => std::fs::metadata(&input).map(|metadata| metadata.len()).unwrap_or(0),
+                            };
+                            println!("Transform: {}", transform.describe(input_len));
+                            if let Ok(untransformed) = untransformed {
+                                println!("Untransformed Hash: {}", byte_order(untransformed));
+                            }
+                        }
                         println!("Output Hash: {}\n", hash);
 
-                        match selection {
-                            0 => println!("SHA-256 is widely used in Bitcoin & general cryptography."),
-                            1 => println!("Keccak-256 is used in Ethereum smart contracts."),
-                            2 => println!("Blake2b is fast and secure. Used in modern protocols like Zcash."),
-                            3 => println!("MD5 is broken. Do NOT use it for security-critical tasks."),
+                        match algorithm {
+                            "SHA-256" => println!("SHA-256 is widely used in Bitcoin & general cryptography."),
+                            "SHA-512/224" => println!("SHA-512/224 runs SHA-512 from its own IV and keeps 224 bits; it is not a truncated SHA-512."),
+                            "Keccak-256" => println!("Keccak-256 is used in Ethereum smart contracts."),
+                            "Blake2b" => println!("Blake2b is fast and secure. Used in modern protocols like Zcash."),
+                            "MD5" => println!("MD5 is broken. Do NOT use it for security-critical tasks."),
+                            "FarmHash64" => println!("FarmHash is a fast hash-table function from Google. It is NOT secure; never use it for integrity or authentication."),
+                            "CRC32" => println!("CRC32 detects accidental corruption in transfers and archives. It is NOT a cryptographic hash: anyone can forge a match."),
                             _ => {}
                         }
                     }
@@ -247,9 +623,44 @@ fn main() {
             2 => {
                 compare_hashes();
             }
+            3 => {
+                bench::benchmarks();
+            }
+            4 => {
+                verify::verification();
+            }
+            5 => {
+                manifest::manifests();
+            }
+            6 => {
+                teaching::teaching_demos();
+            }
+            7 => {
+                kdf::key_derivation();
+            }
+            8 => {
+                archive::archives();
+            }
+            9 => {
+                advanced::advanced_algorithms();
+            }
+            10 => {
+                analysis::analysis();
+            }
+            11 => {
+                mac::message_authentication();
+            }
+            12 => {
+                editor::edit_and_hash();
+            }
+            13 => {
+                ethereum::ethereum();
+            }
             _ => unreachable!(),
         }
 
+        report_read_retries(false);
+
         let continue_choices = vec!["Continue Hashing", "Exit"];
         let continue_selection = Select::new()
             .items(&continue_choices)
@@ -0,0 +1,252 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use dialoguer::{Input, Select};
+
+use crate::algorithms::{hash_file, select_algorithm};
+use crate::audit;
+use crate::output::format_coreutils;
+use crate::torrent;
+
+const HEADER_PREFIX: &str = "# hashing-demo manifest algorithm=";
+
+/// A parsed manifest: the algorithm from its header (if any) and a
+/// path -> hex digest map.
+pub struct Manifest {
+    pub algorithm: Option<String>,
+    pub entries: BTreeMap<String, String>,
+}
+
+pub fn manifests() {
+    let manifest_choices = vec!["Generate Manifest", "Diff Manifests", "BitTorrent v2 Merkle Root"];
+    let manifest_selection = Select::new()
+        .with_prompt("Choose a manifest mode")
+        .items(&manifest_choices)
+        .default(0)
+        .interact()
+        .unwrap();
+
+    match manifest_selection {
+        0 => generate_manifest_interactive(),
+        1 => diff_manifests_interactive(),
+        2 => torrent::torrent_v2_interactive(),
+        _ => unreachable!(),
+    }
+}
+
+fn generate_manifest_interactive() {
+    let dir: String = Input::new()
+        .with_prompt("Enter directory to hash")
+        .interact_text()
+        .unwrap();
+
+    let algorithm = select_algorithm();
+
+    let output_path: String = Input::new()
+        .with_prompt("Write manifest to")
+        .default("manifest.txt".to_string())
+        .interact_text()
+        .unwrap();
+
+    let result = generate_manifest(&dir, algorithm, &output_path)
+        .and_then(|manifest| {
+            fs::write(&output_path, render_manifest(&manifest))?;
+            Ok(manifest)
+        });
+
+    match result {
+        Ok(manifest) => {
+            println!("\nWrote {} entries to '{}' using {}\n", manifest.entries.len(), output_path, algorithm);
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+        }
+    }
+}
+
+fn diff_manifests_interactive() {
+    let old_path: String = Input::new()
+        .with_prompt("Enter old manifest path")
+        .interact_text()
+        .unwrap();
+
+    let new_path: String = Input::new()
+        .with_prompt("Enter new manifest path")
+        .interact_text()
+        .unwrap();
+
+    let old = match read_manifest(&old_path) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            eprintln!("Error with old manifest: {}", e);
+            return;
+        }
+    };
+
+    let new = match read_manifest(&new_path) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            eprintln!("Error with new manifest: {}", e);
+            return;
+        }
+    };
+
+    if old.algorithm != new.algorithm {
+        println!(
+            "\nWarning: manifests use different algorithms ({} vs {}); every shared path will show as changed.",
+            old.algorithm.as_deref().unwrap_or("unknown"),
+            new.algorithm.as_deref().unwrap_or("unknown"),
+        );
+    }
+
+    let mut added = 0;
+    let mut removed = 0;
+    let mut changed = 0;
+
+    println!("\nManifest Diff:");
+    for (path, digest) in &old.entries {
+        match new.entries.get(path) {
+            None => {
+                println!("- {}  {}", path, digest);
+                removed += 1;
+            }
+            Some(new_digest) if new_digest != digest => {
+                println!("~ {}  {} -> {}", path, digest, new_digest);
+                changed += 1;
+            }
+            Some(_) => {}
+        }
+    }
+    for (path, digest) in &new.entries {
+        if !old.entries.contains_key(path) {
+            println!("+ {}  {}", path, digest);
+            added += 1;
+        }
+    }
+
+    let unchanged = old.entries.len() - removed - changed;
+    println!("\nAdded: {}, Removed: {}, Changed: {}, Unchanged: {}\n", added, removed, changed, unchanged);
+}
+
+/// Hashes every regular file under `dir`, keyed by its `/`-separated path
+/// relative to `dir`. `skip` is left out so a manifest written inside the
+/// tree doesn't list itself.
+pub fn generate_manifest(dir: &str, algorithm: &str, skip: &str) -> Result<Manifest, Box<dyn std::error::Error>> {
+    let root = Path::new(dir);
+    if !root.is_dir() {
+        return Err(format!("'{}' is not a directory", dir).into());
+    }
+
+    let skip = fs::canonicalize(skip).ok();
+    let mut entries = BTreeMap::new();
+    for relative in list_files(root)? {
+        let full = root.join(&relative);
+        if skip.is_some() && fs::canonicalize(&full).ok() == skip {
+            continue;
+        }
+        let full = full.to_string_lossy();
+        let hash = hash_file(&full, algorithm)?;
+        audit::record(algorithm, &full, &hash);
+        entries.insert(relative, hash);
+    }
+
+    Ok(Manifest { algorithm: Some(algorithm.to_string()), entries })
+}
+
+/// Relative paths of all regular files under `root`, sorted.
+pub fn list_files(root: &Path) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut files = Vec::new();
+    let mut pending = vec![String::new()];
+
+    while let Some(relative) = pending.pop() {
+        for entry in fs::read_dir(root.join(&relative))? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let path = if relative.is_empty() { name } else { format!("{}/{}", relative, name) };
+
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if file_type.is_file() {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+pub fn render_manifest(manifest: &Manifest) -> String {
+    let mut text = String::new();
+    if let Some(algorithm) = &manifest.algorithm {
+        text.push_str(HEADER_PREFIX);
+        text.push_str(algorithm);
+        text.push('\n');
+    }
+    for (path, digest) in &manifest.entries {
+        text.push_str(&format_coreutils(digest, path));
+        text.push('\n');
+    }
+    text
+}
+
+pub fn read_manifest(path: &str) -> Result<Manifest, Box<dyn std::error::Error>> {
+    let text = fs::read_to_string(path)?;
+    parse_manifest(&text)
+}
+
+/// Parses a manifest in `sha256sum` format, with an optional algorithm
+/// header. Plain checksum files without the header are accepted too.
+pub fn parse_manifest(text: &str) -> Result<Manifest, Box<dyn std::error::Error>> {
+    let mut algorithm = None;
+    let mut entries = BTreeMap::new();
+
+    for (number, line) in text.lines().enumerate() {
+        if let Some(name) = line.strip_prefix(HEADER_PREFIX) {
+            algorithm = Some(name.trim().to_string());
+            continue;
+        }
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (escaped, line) = match line.strip_prefix('\\') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+
+        // `sha256sum -b` marks binary mode with `*` instead of the second space.
+        let (digest, name) = line
+            .split_once("  ")
+            .or_else(|| line.split_once(" *"))
+            .ok_or_else(|| format!("line {}: expected '<digest>  <path>'", number + 1))?;
+
+        if digest.is_empty() || !digest.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("line {}: '{}' is not a hex digest", number + 1, digest).into());
+        }
+
+        let name = if escaped { unescape_name(name) } else { name.to_string() };
+        entries.insert(name, digest.to_ascii_lowercase());
+    }
+
+    Ok(Manifest { algorithm, entries })
+}
+
+fn unescape_name(name: &str) -> String {
+    let mut unescaped = String::with_capacity(name.len());
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
@@ -0,0 +1,104 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::verify::DirectoryReport;
+
+/// `--prometheus` value that sends metrics to stdout.
+pub const STDOUT: &str = "-";
+
+/// Accumulates metric families in the Prometheus text exposition format,
+/// for scraping through the node_exporter textfile collector or a
+/// pushgateway. Every metric is a gauge describing the run that wrote it.
+#[derive(Default)]
+pub struct Exposition {
+    text: String,
+}
+
+impl Exposition {
+    /// Adds a gauge family: its `# HELP` and `# TYPE` lines, then one line
+    /// per sample.
+    pub fn gauge(&mut self, name: &str, help: &str, samples: &[(Vec<(&str, &str)>, f64)]) {
+        self.text.push_str(&format!("# HELP {} {}\n", name, help));
+        self.text.push_str(&format!("# TYPE {} gauge\n", name));
+        for (labels, value) in samples {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(label, value)| format!("{}=\"{}\"", label, escape_label(value)))
+                .collect();
+            self.text.push_str(&format!("{}{{{}}} {}\n", name, labels.join(","), value));
+        }
+    }
+
+    /// Writes the metrics to stdout, or to `target` by writing a temporary
+    /// file beside it and renaming it into place, so a collector reading the
+    /// file never sees half of it.
+    pub fn write(&self, target: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if target == STDOUT {
+            io::stdout().write_all(self.text.as_bytes())?;
+            return Ok(());
+        }
+        let temp = Path::new(target).with_extension("prom.tmp");
+        fs::write(&temp, &self.text).map_err(|e| format!("Cannot write '{}': {}", temp.display(), e))?;
+        fs::rename(&temp, target).map_err(|e| format!("Cannot write '{}': {}", target, e).into())
+    }
+}
+
+/// Label values may hold any UTF-8, with backslash, double quote and
+/// newline escaped.
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// One hashed input as the hash metrics describe it.
+pub struct HashSample<'a> {
+    pub input: &'a str,
+    pub digest: &'a str,
+    pub bytes: u64,
+    pub seconds: f64,
+}
+
+/// One sample per input, labeled `algo` and `input`:
+/// - `hash_duration_seconds`: time spent reading and hashing the input
+/// - `hash_bytes`: the input's size
+/// - `hash_info`: always 1, with the digest as a `digest` label
+pub fn hash_metrics(algorithm: &str, samples: &[HashSample]) -> Exposition {
+    let mut exposition = Exposition::default();
+    let labeled = |value: &dyn Fn(&HashSample) -> f64| -> Vec<(Vec<(&str, &str)>, f64)> {
+        samples
+            .iter()
+            .map(|sample| (vec![("algo", algorithm), ("input", sample.input)], value(sample)))
+            .collect()
+    };
+    exposition.gauge("hash_duration_seconds", "Seconds spent reading and hashing the input.", &labeled(&|sample| sample.seconds));
+    exposition.gauge("hash_bytes", "Size of the hashed input in bytes.", &labeled(&|sample| sample.bytes as f64));
+
+    let info: Vec<(Vec<(&str, &str)>, f64)> = samples
+        .iter()
+        .map(|sample| (vec![("algo", algorithm), ("input", sample.input), ("digest", sample.digest)], 1.0))
+        .collect();
+    exposition.gauge("hash_info", "The input's digest, as a label; always 1.", &info);
+    exposition
+}
+
+/// One sample each, labeled `dir` and `manifest`:
+/// - `verify_duration_seconds`: time spent on the whole check
+/// - `verify_files_ok`, `verify_files_changed`, `verify_files_missing`,
+///   `verify_files_new`, `verify_files_unreadable`: files in each state
+/// - `verify_success`: 1 if the directory matched the manifest, else 0
+pub fn verify_metrics(dir: &str, manifest: &str, report: &DirectoryReport, seconds: f64) -> Exposition {
+    let mut exposition = Exposition::default();
+    let labels = vec![("dir", dir), ("manifest", manifest)];
+    let gauge = |exposition: &mut Exposition, name: &str, help: &str, value: f64| {
+        exposition.gauge(name, help, &[(labels.clone(), value)]);
+    };
+
+    gauge(&mut exposition, "verify_duration_seconds", "Seconds spent verifying the directory.", seconds);
+    gauge(&mut exposition, "verify_files_ok", "Files whose digest matches the manifest.", report.ok as f64);
+    gauge(&mut exposition, "verify_files_changed", "Files whose digest differs from the manifest.", report.changed.len() as f64);
+    gauge(&mut exposition, "verify_files_missing", "Files in the manifest but not on disk.", report.missing.len() as f64);
+    gauge(&mut exposition, "verify_files_new", "Files on disk but not in the manifest.", report.new.len() as f64);
+    gauge(&mut exposition, "verify_files_unreadable", "Files that could not be read.", report.unreadable.len() as f64);
+    gauge(&mut exposition, "verify_success", "1 if the directory matches the manifest, else 0.", if report.passed() { 1.0 } else { 0.0 });
+    exposition
+}
@@ -0,0 +1,456 @@
+use base64::Engine;
+use clap::ValueEnum;
+use uuid::{Builder, Uuid};
+
+#[cfg(feature = "plugins")]
+use crate::plugin;
+
+pub const OUTPUT_FORMATS: &[&str] = &[
+    "Default",
+    "Coreutils (sha256sum)",
+    "BSD Tag (sha256sum --tag)",
+    "Base58",
+    "Base58Check",
+    "BIP-39 Words",
+    "IPv6-Style Groups",
+    "Emoji Fingerprint",
+    "Abbreviated (git short hash)",
+    "Decimal Integer",
+    "Color Swatch",
+    "Nix Base32",
+    "UUID",
+    #[cfg(feature = "identicon")]
+    "Identicon PNG",
+];
+
+/// Hex digits kept by an abbreviated hash, Git's default `core.abbrev`.
+pub const DEFAULT_ABBREV: usize = 7;
+
+/// Words in a BIP-39 fingerprint: 66 bits is enough to make an accidental
+/// match unlikely while staying short enough to read aloud.
+const FINGERPRINT_WORDS: usize = 6;
+
+/// Colors in a color swatch, 3 digest bytes each.
+const SWATCH_COLORS: usize = 8;
+
+/// Nix's base32 alphabet: digits and lowercase letters minus `e`, `o`, `u`
+/// and `t`, so no store path spells a word.
+const NIX_BASE32_ALPHABET: &[u8; 32] = b"0123456789abcdfghijklmnpqrsvwxyz";
+
+/// Emoji in an emoji fingerprint, 6 bits each.
+const FINGERPRINT_EMOJI: usize = 8;
+
+/// 64 single-code-point emoji that render as pictures by default and are easy
+/// to tell apart at a glance; no skin tones, flags or joined sequences, which
+/// render inconsistently across terminals.
+const EMOJI_TABLE: [&str; 64] = [
+    "🐶", "🐱", "🐭", "🐹", "🐰", "🦊", "🐻", "🐼",
+    "🐨", "🐯", "🦁", "🐮", "🐷", "🐸", "🐵", "🐔",
+    "🐧", "🐦", "🐤", "🦆", "🦉", "🐺", "🐗", "🐴",
+    "🦄", "🐝", "🐛", "🦋", "🐌", "🐞", "🐢", "🐍",
+    "🐙", "🦑", "🦀", "🐡", "🐠", "🐬", "🐳", "🐊",
+    "🍎", "🍐", "🍊", "🍋", "🍌", "🍉", "🍇", "🍓",
+    "🍒", "🍑", "🍍", "🥝", "🥕", "🌽", "🍄", "🌵",
+    "🌻", "🌙", "🌈", "🔥", "💧", "🎈", "🔑", "🚀",
+];
+
+/// Tag used by the `--tag` output of the GNU/BSD checksum tools. Keccak-256
+/// has no coreutils counterpart, so it uses the name OpenSSL prints;
+/// SHA-512/224 uses the one from Perl's `shasum --tag`;
+/// FarmHash64 and CRC32 have neither and just get their upper-cased names.
+/// Plugins are tagged with their registered names.
+pub fn bsd_tag(algorithm: &str) -> &'static str {
+    match algorithm {
+        "SHA-256" => "SHA256",
+        "SHA-512/224" => "SHA512/224",
+        "Keccak-256" => "KECCAK-256",
+        "Blake2b" => "BLAKE2b",
+        "MD5" => "MD5",
+        "FarmHash64" => "FARMHASH64",
+        "CRC32" => "CRC32",
+        #[cfg(feature = "plugins")]
+        name if plugin::find(name).is_some() => plugin::find(name).unwrap().name,
+        _ => unreachable!(),
+    }
+}
+
+/// Algorithms whose digest is an integer rather than a byte string.
+pub const INTEGER_ALGORITHMS: &[&str] = &["FarmHash64", "CRC32"];
+
+/// Whether the algorithm's digest is an integer rather than a byte string,
+/// leaving tools to disagree on the byte order they print it in.
+///
+/// FarmHash64 and CRC32 are shown big-endian by default, most significant
+/// byte first, the way `printf("%016llx")` prints the u64 and `crc32`(1)
+/// prints the checksum. Little-endian is how the value sits in memory on x86
+/// and ARM, and what tools that dump the raw bytes show. SHA-2, Keccak,
+/// Blake2b and MD5 define their output as a sequence of bytes, so there is no
+/// byte order to choose.
+pub fn has_byte_order(algorithm: &str) -> bool {
+    INTEGER_ALGORITHMS.contains(&algorithm)
+}
+
+/// The digest's bytes in reverse order, turning a big-endian integer
+/// display into a little-endian one and back.
+pub fn reverse_bytes(hash: &str) -> String {
+    let mut bytes = hex::decode(hash).unwrap();
+    bytes.reverse();
+    hex::encode(bytes)
+}
+
+/// `<hex>  <name>`, as printed by `sha256sum`.
+pub fn format_coreutils(hash: &str, name: &str) -> String {
+    let (prefix, name) = escape_name(name);
+    format!("{}{}  {}", prefix, hash, name)
+}
+
+/// `<TAG> (<name>) = <hex>`, as printed by `sha256sum --tag`.
+pub fn format_bsd(algorithm: &str, hash: &str, name: &str) -> String {
+    let (prefix, name) = escape_name(name);
+    format!("{}{} ({}) = {}", prefix, bsd_tag(algorithm), name, hash)
+}
+
+/// The raw digest bytes in Base58 (Bitcoin alphabet).
+pub fn format_base58(hash: &str) -> String {
+    bs58::encode(hex::decode(hash).unwrap()).into_string()
+}
+
+/// Base58Check: the payload followed by the first 4 bytes of its double
+/// SHA-256. Bitcoin addresses prefix the payload with a version byte (0x00
+/// for P2PKH), which is what `version` adds.
+pub fn format_base58check(hash: &str, version: Option<u8>) -> String {
+    let bytes = hex::decode(hash).unwrap();
+    match version {
+        Some(version) => bs58::encode(bytes).with_check_version(version).into_string(),
+        None => bs58::encode(bytes).with_check().into_string(),
+    }
+}
+
+/// Maps the leading bits of the digest, 11 at a time, onto the BIP-39
+/// English word list. This is a truncated fingerprint for comparing hashes
+/// by voice, not an encoding of the full digest, and unlike a BIP-39
+/// mnemonic it carries no checksum.
+pub fn format_bip39_words(hash: &str) -> String {
+    let bytes = hex::decode(hash).unwrap();
+    let words = bip39::Language::English.word_list();
+    let count = FINGERPRINT_WORDS.min(bytes.len() * 8 / 11);
+
+    (0..count)
+        .map(|i| {
+            words[read_bits(&bytes, i * 11, 11)]
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The first 128 bits as eight colon-separated groups of four hex digits,
+/// like a fully expanded IPv6 address. Zero groups are never compressed to
+/// `::`, so two fingerprints always line up group by group. Digests shorter
+/// than 128 bits give fewer groups.
+pub fn format_ipv6(hash: &str) -> String {
+    let bytes = hex::decode(hash).unwrap();
+    bytes
+        .chunks_exact(2)
+        .take(8)
+        .map(|pair| format!("{:02x}{:02x}", pair[0], pair[1]))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// How a digest turned into a UUID is marked.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum UuidStyle {
+    /// The first 16 bytes as they are; most likely not a valid RFC 9562 UUID.
+    Raw,
+    /// Version and variant bits overwritten to make a UUIDv8, the version
+    /// set aside for custom layouts like this one.
+    V8,
+}
+
+/// The first 16 bytes of the digest in UUID form,
+/// `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx`, for systems that key records on
+/// UUIDs. Everything past 128 bits is discarded, and `V8` overwrites 6 of
+/// the rest, leaving 122: a stable identifier for content, not a digest to
+/// verify it with. Digests shorter than 16 bytes are an error.
+pub fn format_uuid(hash: &str, style: UuidStyle) -> Result<String, String> {
+    let bytes = hex::decode(hash).unwrap();
+    let Some(first) = bytes.first_chunk::<16>() else {
+        return Err(format!("a UUID needs 16 bytes, but the digest has only {}", bytes.len()));
+    };
+    let uuid = match style {
+        UuidStyle::Raw => Uuid::from_bytes(*first),
+        UuidStyle::V8 => Builder::from_custom_bytes(*first).into_uuid(),
+    };
+    Ok(uuid.hyphenated().to_string())
+}
+
+/// Maps the leading bits of the digest, 6 at a time, onto a fixed table of
+/// emoji: a short visual fingerprint for eyeballing that two hashes agree,
+/// in the spirit of the emoji codes some apps show when pairing devices. It
+/// covers only the first 48 bits and is a human-comparison aid only, never a
+/// substitute for comparing the full digest.
+pub fn format_emoji(hash: &str) -> String {
+    let bytes = hex::decode(hash).unwrap();
+    let count = FINGERPRINT_EMOJI.min(bytes.len() * 8 / 6);
+
+    (0..count)
+        .map(|i| EMOJI_TABLE[read_bits(&bytes, i * 6, 6)])
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The digest in Nix's base32, as printed by `nix hash convert --to nix32`
+/// and used for the `sha256` of fixed-output derivations. This is specific
+/// to Nix and is not RFC 4648 base32: besides its own alphabet, it reads the
+/// digest as one little-endian number and prints it most significant digit
+/// first, so the last character comes from the low bits of the first byte.
+/// A SHA-256 digest gives 52 characters. Store path hashes are shorter
+/// because Nix first folds its digest down to 20 bytes.
+pub fn format_nix_base32(hash: &str) -> String {
+    let bytes = hex::decode(hash).unwrap();
+    let len = (bytes.len() * 8).div_ceil(5);
+
+    (0..len)
+        .rev()
+        .map(|digit| {
+            let bit = digit * 5;
+            let (index, shift) = (bit / 8, bit % 8);
+            let low = bytes[index] >> shift;
+            let high = bytes.get(index + 1).map_or(0, |&next| (next as u16) << (8 - shift)) as u8;
+            NIX_BASE32_ALPHABET[((low | high) & 0x1f) as usize] as char
+        })
+        .collect()
+}
+
+/// The bytes as `hexdump -C` shows them: an offset, 16 bytes in hex, and
+/// the same bytes as ASCII with `.` for anything unprintable; runs of
+/// identical lines collapse to `*`, and the last line is the total length.
+/// Only the first `limit` bytes are shown; the rest are counted in a note.
+pub fn hexdump(bytes: &[u8], limit: usize) -> String {
+    let shown = &bytes[..limit.min(bytes.len())];
+    let mut lines = Vec::new();
+    let mut previous: Option<&[u8]> = None;
+    let mut collapsed = false;
+
+    for (index, line) in shown.chunks(16).enumerate() {
+        if line.len() == 16 && previous == Some(line) {
+            if !collapsed {
+                lines.push("*".to_string());
+                collapsed = true;
+            }
+            continue;
+        }
+        previous = Some(line);
+        collapsed = false;
+
+        let mut hex = String::new();
+        for (column, byte) in line.iter().enumerate() {
+            hex.push_str(&format!("{:02x} ", byte));
+            if column == 7 {
+                hex.push(' ');
+            }
+        }
+        let ascii: String = line
+            .iter()
+            .map(|&byte| if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' })
+            .collect();
+        lines.push(format!("{:08x}  {:<49} |{}|", index * 16, hex, ascii));
+    }
+
+    if shown.len() < bytes.len() {
+        lines.push(format!("... ({} of {} bytes shown)", shown.len(), bytes.len()));
+    } else {
+        lines.push(format!("{:08x}", bytes.len()));
+    }
+    lines.join("\n")
+}
+
+/// The first `len` hex digits, like `git log --oneline`. Only 4 bits of
+/// collision resistance survive per digit kept: 7 digits collide by chance
+/// after a few thousand digests, which is why Git lengthens them as a
+/// repository grows.
+pub fn format_abbrev(hash: &str, len: usize) -> &str {
+    &hash[..len.min(hash.len())]
+}
+
+/// One step of an `--out` pipeline.
+#[derive(Clone, Copy)]
+pub enum OutputStep {
+    /// Keep the first N bytes of the digest.
+    Truncate(usize),
+    /// Reverse the digest's bytes.
+    Reverse,
+    Hex,
+    Base64,
+    /// Uppercase hex digits.
+    Uppercase,
+}
+
+/// Steps applied in order to a digest: byte steps first, then one encoding,
+/// then text steps.
+#[derive(Clone)]
+pub struct OutputPipeline(Vec<OutputStep>);
+
+/// Parses a comma-separated pipeline such as `truncate:8,base64` or
+/// `reverse,hex,uppercase`:
+///
+/// - `truncate:N` and `reverse` work on the raw digest bytes and must come
+///   before the encoding;
+/// - `hex` or `base64` encodes the bytes, at most once; without either the
+///   bytes are hex-encoded at the end;
+/// - `uppercase` comes after `hex`, since uppercasing base64 changes its
+///   value.
+pub fn parse_pipeline(spec: &str) -> Result<OutputPipeline, String> {
+    let mut steps = Vec::new();
+    let mut encoding = None;
+    for step in spec.split(',').map(str::trim) {
+        let parsed = match step.split_once(':') {
+            Some(("truncate", len)) => match len.parse::<usize>() {
+                Ok(len) if len > 0 => OutputStep::Truncate(len),
+                _ => return Err(format!("'{}': truncate needs a byte count of at least 1", step)),
+            },
+            None if step == "reverse" => OutputStep::Reverse,
+            None if step == "hex" => OutputStep::Hex,
+            None if step == "base64" => OutputStep::Base64,
+            None if step == "uppercase" => OutputStep::Uppercase,
+            _ if step.is_empty() => return Err(format!("'{}': empty step", spec)),
+            _ => return Err(format!("unknown output step '{}'; expected truncate:N, reverse, hex, base64 or uppercase", step)),
+        };
+
+        match (parsed, encoding) {
+            (OutputStep::Truncate(_) | OutputStep::Reverse, Some(encoding)) => {
+                return Err(format!("'{}' works on digest bytes and must come before '{}'", step, encoding));
+            }
+            (OutputStep::Hex | OutputStep::Base64, Some(encoding)) => {
+                return Err(format!("'{}': the digest is already encoded by '{}'", step, encoding));
+            }
+            (OutputStep::Hex | OutputStep::Base64, None) => encoding = Some(step),
+            (OutputStep::Uppercase, encoding) if encoding != Some("hex") => {
+                return Err("'uppercase' applies to hex output; put it after 'hex'".into());
+            }
+            _ => {}
+        }
+        steps.push(parsed);
+    }
+
+    if encoding.is_none() {
+        steps.push(OutputStep::Hex);
+    }
+    Ok(OutputPipeline(steps))
+}
+
+impl OutputPipeline {
+    /// Runs the pipeline on a hex digest. A truncation longer than the
+    /// digest keeps all of it.
+    pub fn apply(&self, hash: &str) -> String {
+        let mut bytes = hex::decode(hash).unwrap();
+        let mut text = String::new();
+        for step in &self.0 {
+            match step {
+                OutputStep::Truncate(len) => bytes.truncate(*len),
+                OutputStep::Reverse => bytes.reverse(),
+                OutputStep::Hex => text = hex::encode(&bytes),
+                OutputStep::Base64 => text = base64::engine::general_purpose::STANDARD.encode(&bytes),
+                OutputStep::Uppercase => text = text.to_ascii_uppercase(),
+            }
+        }
+        text
+    }
+}
+
+/// The first `width` bytes of the digest (8 or 16) read as a big-endian
+/// unsigned integer and printed in decimal, for sharding or bucketing by
+/// `value % buckets`. The rest of the digest is thrown away, so this is a
+/// bucketing key only and carries none of the hash's security. A digest
+/// shorter than `width` uses all of its bytes.
+pub fn format_integer(hash: &str, width: usize) -> String {
+    let bytes = hex::decode(hash).unwrap();
+    let value = bytes.iter().take(width).fold(0u128, |acc, &byte| acc << 8 | byte as u128);
+    value.to_string()
+}
+
+/// How much color the terminal can show, for `format_swatch`.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ColorSupport {
+    TrueColor,
+    Ansi256,
+    None,
+}
+
+/// Guesses color support the way most CLI tools do: `COLORTERM` advertises
+/// 24-bit color, a `TERM` ending in `256color` the xterm palette, and
+/// anything else (or output that isn't a terminal, or `NO_COLOR`) gets text.
+pub fn color_support() -> ColorSupport {
+    use std::io::IsTerminal;
+
+    if !std::io::stdout().is_terminal() || std::env::var_os("NO_COLOR").is_some() {
+        return ColorSupport::None;
+    }
+    let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+    if colorterm == "truecolor" || colorterm == "24bit" {
+        return ColorSupport::TrueColor;
+    }
+    if std::env::var("TERM").is_ok_and(|term| term.ends_with("256color")) {
+        return ColorSupport::Ansi256;
+    }
+    ColorSupport::None
+}
+
+/// Reads the leading digest bytes three at a time as RGB colors and prints
+/// each as a colored block, in the spirit of identicons: a glanceable
+/// identity for the hash, not a way to compare it. Without color support
+/// the same colors are printed as `#rrggbb` codes.
+pub fn format_swatch(hash: &str, support: ColorSupport) -> String {
+    let bytes = hex::decode(hash).unwrap();
+    let colors = bytes.chunks_exact(3).take(SWATCH_COLORS).map(|rgb| (rgb[0], rgb[1], rgb[2]));
+
+    colors
+        .map(|(r, g, b)| match support {
+            ColorSupport::TrueColor => format!("\x1b[38;2;{};{};{}m\u{2588}\u{2588}\u{2588}\x1b[0m", r, g, b),
+            ColorSupport::Ansi256 => format!("\x1b[38;5;{}m\u{2588}\u{2588}\u{2588}\x1b[0m", nearest_ansi256(r, g, b)),
+            ColorSupport::None => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        })
+        .collect::<Vec<_>>()
+        .join(if support == ColorSupport::None { " " } else { "" })
+}
+
+/// The closest entry of the xterm 256-color palette, from its 6x6x6 color
+/// cube or its 24-step gray ramp, by squared RGB distance.
+fn nearest_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    let nearest_level = |value: u8| (0..6).min_by_key(|&i| (LEVELS[i] as i32 - value as i32).abs()).unwrap();
+    let distance = |(r2, g2, b2): (u8, u8, u8)| {
+        [(r, r2), (g, g2), (b, b2)].iter().map(|&(a, b)| (a as i32 - b as i32).pow(2)).sum::<i32>()
+    };
+
+    let (ri, gi, bi) = (nearest_level(r), nearest_level(g), nearest_level(b));
+    let cube = (16 + 36 * ri + 6 * gi + bi) as u8;
+    let cube_distance = distance((LEVELS[ri], LEVELS[gi], LEVELS[bi]));
+
+    let average = (r as i32 + g as i32 + b as i32) / 3;
+    let gray_index = ((average - 8).max(0) / 10).min(23) as u8;
+    let gray = 8 + 10 * gray_index;
+    if distance((gray, gray, gray)) < cube_distance { 232 + gray_index } else { cube }
+}
+
+/// The `count` bits starting at bit `start`, most significant bit first.
+fn read_bits(bytes: &[u8], start: usize, count: usize) -> usize {
+    (start..start + count).fold(0, |acc, position| {
+        let set = bytes[position / 8] >> (7 - position % 8) & 1;
+        acc << 1 | set as usize
+    })
+}
+
+/// Coreutils escapes backslashes and newlines in file names and marks such
+/// lines with a leading backslash so `--check` can undo it.
+fn escape_name(name: &str) -> (&'static str, String) {
+    if !name.contains(['\\', '\n', '\r']) {
+        return ("", name.to_string());
+    }
+
+    let escaped = name
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r");
+    ("\\", escaped)
+}
@@ -0,0 +1,227 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::thread;
+use serde::{Deserialize, Serialize};
+
+use crate::algorithms::ALGORITHMS;
+
+/// Config file, in the home directory, used when `--plugins` isn't given.
+/// It is never looked for in the working directory, so running the program
+/// in someone else's checkout can't pick up their commands.
+pub const DEFAULT_CONFIG: &str = ".hashing-demo-plugins.json";
+
+/// A registered command, as stored in the config file under its name.
+#[derive(Serialize, Deserialize)]
+struct PluginEntry {
+    command: String,
+}
+
+/// An external command registered as a hash algorithm.
+pub struct Plugin {
+    pub name: &'static str,
+    pub command: String,
+}
+
+static PLUGINS: OnceLock<Vec<Plugin>> = OnceLock::new();
+
+/// `--plugins` if given, else `DEFAULT_CONFIG` in `$HOME`.
+pub fn config_path(explicit: Option<&str>) -> Option<PathBuf> {
+    match explicit {
+        Some(path) => Some(PathBuf::from(path)),
+        None => std::env::var_os("HOME").map(|home| Path::new(&home).join(DEFAULT_CONFIG)),
+    }
+}
+
+/// Reads the registered commands so they join the algorithm registry. Must
+/// run before anything looks an algorithm up. Problems are reported and the
+/// affected plugins skipped; nothing is executed here.
+pub fn load(explicit: Option<&str>) {
+    let mut plugins = Vec::new();
+    if let Some(path) = config_path(explicit) {
+        match read_config(&path, explicit.is_some()) {
+            Ok(config) => {
+                for (name, entry) in config {
+                    match validate_name(&name).and_then(|_| find_program(&entry.command)) {
+                        Ok(()) => plugins.push(Plugin { name: Box::leak(name.into_boxed_str()), command: entry.command }),
+                        Err(e) => eprintln!("Warning: skipping plugin '{}' in '{}': {}", name, path.display(), e),
+                    }
+                }
+            }
+            Err(e) => eprintln!("Warning: ignoring plugins: {}", e),
+        }
+    }
+    let _ = PLUGINS.set(plugins);
+}
+
+pub fn plugins() -> &'static [Plugin] {
+    PLUGINS.get().map(|plugins| plugins.as_slice()).unwrap_or(&[])
+}
+
+pub fn find(name: &str) -> Option<&'static Plugin> {
+    plugins().iter().find(|plugin| plugin.name == name)
+}
+
+impl Plugin {
+    pub fn digest(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        run_command(self.name, &self.command, data)
+    }
+}
+
+/// Runs a command through the shell with `data` on stdin and reads the
+/// digest from stdout: the first whitespace-separated word, in hex with an
+/// optional `0x`. Anything after it, like the `-` that `sha256sum` prints
+/// for stdin, is ignored. Fails if the command exits nonzero, stops reading
+/// its input early, or prints no hex digest.
+fn run_command(name: &str, command: &str, data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut child = shell(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("plugin '{}': cannot run '{}': {}", name, command, e))?;
+
+    // Feed stdin from another thread, so a command that writes output
+    // before it has read all its input can't deadlock against us.
+    let mut stdin = child.stdin.take().unwrap();
+    let (written, output) = thread::scope(|scope| {
+        let writer = scope.spawn(move || stdin.write_all(data));
+        let output = child.wait_with_output();
+        (writer.join().unwrap(), output)
+    });
+    let output = output.map_err(|e| format!("plugin '{}': {}", name, e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("plugin '{}': '{}' failed ({}): {}", name, command, output.status, stderr.trim()));
+    }
+    match written {
+        Err(e) if e.kind() == ErrorKind::BrokenPipe => {
+            return Err(format!("plugin '{}': '{}' exited without reading all its input", name, command));
+        }
+        Err(e) => return Err(format!("plugin '{}': writing its input: {}", name, e)),
+        Ok(()) => {}
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let word = stdout.split_whitespace().next().unwrap_or("");
+    let word = word.strip_prefix("0x").unwrap_or(word);
+    match hex::decode(word) {
+        Ok(digest) if !digest.is_empty() => Ok(digest),
+        _ => Err(format!("plugin '{}': '{}' printed no hex digest on stdout", name, command)),
+    }
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(not(unix))]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+/// Plugin names are used like built-in algorithm names, so they can't take
+/// a built-in's name or contain the `:` that starts spec parameters.
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() || name.trim() != name {
+        return Err("a plugin name can't be empty or start or end with spaces".to_string());
+    }
+    if name.contains(':') {
+        return Err(format!("'{}': a plugin name can't contain ':'", name));
+    }
+    if let Some(builtin) = ALGORITHMS.iter().find(|algorithm| algorithm.eq_ignore_ascii_case(name)) {
+        return Err(format!("'{}' is the built-in {}", name, builtin));
+    }
+    Ok(())
+}
+
+/// Checks that the command's first word names a program that exists: a path
+/// to an executable file, or one found on `PATH`. The shell is free to
+/// interpret the rest, so pipes and arguments work, but the first word must
+/// be the program rather than a variable assignment or a shell builtin.
+fn find_program(command: &str) -> Result<(), String> {
+    let program = command.split_whitespace().next().ok_or("the command is empty")?;
+    let found = if program.contains(std::path::is_separator) {
+        is_executable(Path::new(program))
+    } else {
+        std::env::var_os("PATH")
+            .is_some_and(|path| std::env::split_paths(&path).any(|dir| is_executable(&dir.join(program))))
+    };
+    if found { Ok(()) } else { Err(format!("'{}' is not an executable program or on PATH", program)) }
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata().is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file() || path.with_extension("exe").is_file()
+}
+
+/// Reads the config, which need not exist unless it was named explicitly.
+/// On Unix a config that others can write is refused: whoever can edit it
+/// chooses what runs.
+fn read_config(path: &Path, explicit: bool) -> Result<BTreeMap<String, PluginEntry>, Box<dyn std::error::Error>> {
+    if !explicit && !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Ok(metadata) = path.metadata()
+            && metadata.permissions().mode() & 0o022 != 0
+        {
+            return Err(format!("'{}' is writable by other users; restrict it with chmod go-w", path.display()).into());
+        }
+    }
+    let contents = fs::read_to_string(path).map_err(|e| format!("Cannot read plugin config '{}': {}", path.display(), e))?;
+    serde_json::from_str(&contents).map_err(|e| format!("Plugin config '{}' is not valid: {}", path.display(), e).into())
+}
+
+fn write_config(path: &Path, config: &BTreeMap<String, PluginEntry>) -> Result<(), Box<dyn std::error::Error>> {
+    let contents = serde_json::to_string_pretty(config)?;
+    fs::write(path, contents + "\n").map_err(|e| format!("Cannot write plugin config '{}': {}", path.display(), e).into())
+}
+
+/// Registers `command` as the algorithm `name`, replacing any command
+/// already registered under it, after checking its program exists and that
+/// it hashes the empty input to a hex digest. Returns that digest.
+///
+/// A plugin is not sandboxed. Its command runs through the shell with the
+/// user's privileges, environment and working directory, and sees every
+/// byte hashed with it, so register only commands you would run by hand on
+/// the same data. The config file is as trusted as a script on `PATH` and is
+/// refused if others can write to it. Builds without the `plugins` feature
+/// never read it or run anything from it.
+pub fn register(config: &Path, name: &str, command: &str) -> Result<String, Box<dyn std::error::Error>> {
+    validate_name(name)?;
+    find_program(command)?;
+    let empty = hex::encode(run_command(name, command, &[])?);
+
+    let mut entries = read_config(config, false)?;
+    entries.insert(name.to_string(), PluginEntry { command: command.to_string() });
+    write_config(config, &entries)?;
+    Ok(empty)
+}
+
+/// Removes a registered plugin. Returns whether it was registered.
+pub fn unregister(config: &Path, name: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    let mut entries = read_config(config, false)?;
+    if entries.remove(name).is_none() {
+        return Ok(false);
+    }
+    write_config(config, &entries)?;
+    Ok(true)
+}
@@ -0,0 +1,74 @@
+use std::io::{self, Read};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::algorithms::check_file;
+
+/// Lowers the scheduling priority of the calling thread to `nice` (0 is
+/// normal, 19 the lowest), so a long hash yields the CPU to everything else.
+/// On Linux the nice value is per thread and threads started afterwards
+/// inherit it, which covers the workers of `--verify-dir`; on other Unixes it
+/// applies to the whole process.
+///
+/// Windows has no nice value; there this returns an error and the hash runs
+/// at normal priority. Starting the program with `start /low` gives the same
+/// effect there.
+#[cfg(unix)]
+pub fn lower_priority(nice: i32) -> Result<(), String> {
+    // SAFETY: setpriority only reads its arguments; `who == 0` means the
+    // calling thread (Linux) or process.
+    let result = unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, nice) };
+    if result == -1 {
+        return Err(format!("cannot set nice value {}: {}", nice, io::Error::last_os_error()));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn lower_priority(nice: i32) -> Result<(), String> {
+    Err(format!("nice value {} not applied: priorities are only supported on Unix", nice))
+}
+
+/// A reader that sleeps as needed to keep its average rate at or below
+/// `bytes_per_sec`, so hashing a large file doesn't saturate the disk. The
+/// rate is measured from the first read, so short bursts are smoothed out
+/// rather than each chunk being delayed on its own.
+pub struct ThrottledReader<R> {
+    inner: R,
+    bytes_per_sec: f64,
+    start: Option<Instant>,
+    consumed: u64,
+}
+
+impl<R: Read> ThrottledReader<R> {
+    pub fn new(inner: R, bytes_per_sec: f64) -> Self {
+        ThrottledReader { inner, bytes_per_sec, start: None, consumed: 0 }
+    }
+}
+
+impl<R: Read> Read for ThrottledReader<R> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let start = *self.start.get_or_insert_with(Instant::now);
+        // Reads of at most a tenth of a second's budget keep the pacing smooth.
+        let limit = buffer.len().min((self.bytes_per_sec / 10.0).max(1.0) as usize);
+        let read = self.inner.read(&mut buffer[..limit])?;
+        self.consumed += read as u64;
+
+        let due = Duration::from_secs_f64(self.consumed as f64 / self.bytes_per_sec);
+        if let Some(ahead) = due.checked_sub(start.elapsed()) {
+            thread::sleep(ahead);
+        }
+        Ok(read)
+    }
+}
+
+/// `read_file`, but read no faster than `mb_per_sec` megabytes (10^6
+/// bytes) per second.
+pub fn read_file_throttled(file_path: &str, mb_per_sec: f64) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    check_file(file_path)?;
+
+    let file = std::fs::File::open(file_path)?;
+    let mut data = Vec::new();
+    ThrottledReader::new(file, mb_per_sec * 1_000_000.0).read_to_end(&mut data)?;
+    Ok(data)
+}
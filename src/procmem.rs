@@ -0,0 +1,63 @@
+use std::fs::File;
+use std::io;
+use std::os::unix::fs::FileExt;
+
+use crate::algorithms::StreamHasher;
+
+const CHUNK_SIZE: u64 = 64 * 1024;
+
+/// Parses `START-END` with hex addresses, as printed in `/proc/<pid>/maps`
+/// (a `0x` prefix is accepted). END is exclusive.
+pub fn parse_range(range: &str) -> Result<(u64, u64), String> {
+    let parse = |address: &str| {
+        let address = address.trim();
+        let address = address.strip_prefix("0x").unwrap_or(address);
+        u64::from_str_radix(address, 16).map_err(|_| format!("'{}' is not a hex address", address))
+    };
+
+    let (start, end) = range.split_once('-').ok_or("range must look like START-END")?;
+    let (start, end) = (parse(start)?, parse(end)?);
+    if end <= start {
+        return Err("range end must be greater than its start".into());
+    }
+    Ok((start, end))
+}
+
+/// Hashes `[start, end)` of another process's address space by streaming
+/// `/proc/<pid>/mem` through the hasher a chunk at a time.
+pub fn hash_process_memory(pid: u32, start: u64, end: u64, algorithm: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let path = format!("/proc/{}/mem", pid);
+    let mem = File::open(&path).map_err(|e| describe_error(pid, &path, e))?;
+
+    let mut hasher = StreamHasher::new(algorithm);
+    let mut buffer = vec![0u8; CHUNK_SIZE as usize];
+    let mut offset = start;
+
+    while offset < end {
+        let len = (end - offset).min(CHUNK_SIZE) as usize;
+        let read = mem.read_at(&mut buffer[..len], offset).map_err(|e| describe_error(pid, &path, e))?;
+        if read == 0 {
+            return Err(format!("unexpected end of memory at 0x{:x}", offset).into());
+        }
+        hasher.update(&buffer[..read]);
+        offset += read as u64;
+    }
+
+    Ok(hex::encode(hasher.finalize()?))
+}
+
+fn describe_error(pid: u32, path: &str, error: io::Error) -> String {
+    match error.kind() {
+        io::ErrorKind::NotFound => format!("no process with PID {}", pid),
+        io::ErrorKind::PermissionDenied => format!(
+            "permission denied reading {}: the target must belong to you and ptrace must be allowed \
+             (see /proc/sys/kernel/yama/ptrace_scope), or run with CAP_SYS_PTRACE",
+            path
+        ),
+        // The kernel reports unmapped addresses as EIO.
+        _ if error.raw_os_error() == Some(libc::EIO) => {
+            format!("address range is not (fully) mapped in PID {}; check /proc/{}/maps", pid, pid)
+        }
+        _ => format!("cannot read {}: {}", path, error),
+    }
+}
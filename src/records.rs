@@ -0,0 +1,56 @@
+use std::io::{self, Read};
+
+/// Largest record accepted. A length beyond this is far more likely to be a
+/// misaligned or non-framed stream than a real record.
+pub const MAX_RECORD_LEN: u32 = 256 * 1024 * 1024;
+
+/// Splits a stream of records, each a 4-byte big-endian length followed by
+/// that many bytes, calling `each` with the index and bytes of every complete
+/// record in order. The stream must end exactly on a record boundary.
+///
+/// Returns the number of records read. On a truncated header or body, or a
+/// length over `MAX_RECORD_LEN`, the records before it have already been
+/// passed to `each` and the error describes the offending record.
+pub fn read_records(
+    mut reader: impl Read,
+    mut each: impl FnMut(usize, Vec<u8>),
+) -> Result<usize, String> {
+    let mut index = 0;
+    loop {
+        let mut header = [0u8; 4];
+        let header_len = read_full(&mut reader, &mut header).map_err(|e| format!("record {}: {}", index, e))?;
+        match header_len {
+            0 => return Ok(index),
+            4 => {}
+            partial => return Err(format!("record {}: truncated length header ({} of 4 bytes)", index, partial)),
+        }
+
+        let len = u32::from_be_bytes(header);
+        if len > MAX_RECORD_LEN {
+            return Err(format!("record {}: length {} exceeds the {}-byte limit", index, len, MAX_RECORD_LEN));
+        }
+
+        let mut record = Vec::new();
+        (&mut reader).take(len as u64).read_to_end(&mut record).map_err(|e| format!("record {}: {}", index, e))?;
+        if record.len() < len as usize {
+            return Err(format!("record {}: truncated, header says {} bytes but only {} remain", index, len, record.len()));
+        }
+
+        each(index, record);
+        index += 1;
+    }
+}
+
+/// Fills `buffer` unless the stream ends first, returning how much was read.
+pub fn read_full(reader: &mut impl Read, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
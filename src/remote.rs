@@ -0,0 +1,95 @@
+use std::io::Read;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::algorithms::{self, hash_file, hash_text};
+use crate::audit;
+use crate::manifest::parse_manifest;
+
+/// Checksum files are a few lines per release artifact; anything much
+/// larger is not one.
+const MAX_SUMS_LEN: u64 = 4 * 1024 * 1024;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A local file checked against its line in a published checksum file.
+pub struct RemoteCheck {
+    pub algorithm: &'static str,
+    /// The file name as listed in the checksum file.
+    pub entry: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl RemoteCheck {
+    pub fn matches(&self) -> bool {
+        self.expected == self.actual
+    }
+}
+
+/// Downloads the checksum file at `url` (`SHA256SUMS` and the like, in
+/// `sha256sum` format), finds the line for `file` by its base name, and
+/// hashes `file` to compare. An entry listed with a directory, such as
+/// `dist/tool.tar.gz`, matches on its last component.
+///
+/// The checksum file's algorithm header wins if it has one; otherwise
+/// `algorithm` is used, and a published digest of the wrong length for it is
+/// an error rather than a mismatch. The checksum file is only as trustworthy
+/// as the connection it came over: fetch it over HTTPS, or check its
+/// signature separately.
+pub fn check_remote(file: &str, url: &str, algorithm: &str) -> Result<RemoteCheck, Box<dyn std::error::Error>> {
+    let name = Path::new(file)
+        .file_name()
+        .ok_or_else(|| format!("'{}' has no file name", file))?
+        .to_string_lossy()
+        .into_owned();
+
+    let text = fetch(url)?;
+    let sums = parse_manifest(&text).map_err(|e| format!("{} is not a checksum file: {}", url, e))?;
+
+    let (entry, expected) = sums
+        .entries
+        .get_key_value(&name)
+        .or_else(|| sums.entries.iter().find(|(entry, _)| Path::new(entry.as_str()).file_name().is_some_and(|entry| *entry == *name)))
+        .ok_or_else(|| format!("{} has no entry for '{}' ({} entries listed)", url, name, sums.entries.len()))?;
+
+    let algorithm = match &sums.algorithm {
+        Some(header) => algorithms::lookup(header)?,
+        None => algorithms::lookup(algorithm)?,
+    };
+    let digest_len = hash_text("", algorithm)?.len();
+    if expected.len() != digest_len {
+        return Err(format!(
+            "the published digest for '{}' has {} hex digits, but {} digests have {}; choose the matching algorithm",
+            entry,
+            expected.len(),
+            algorithm,
+            digest_len,
+        )
+        .into());
+    }
+
+    let actual = hash_file(file, algorithm)?;
+    audit::record(algorithm, file, &actual);
+    Ok(RemoteCheck { algorithm, entry: entry.clone(), expected: expected.clone(), actual })
+}
+
+fn fetch(url: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let agent = ureq::AgentBuilder::new().timeout(TIMEOUT).build();
+    let response = agent.get(url).call().map_err(|e| match e {
+        ureq::Error::Status(code, response) => format!("{} returned HTTP {} {}", url, code, response.status_text()),
+        // The transport error already names the URL.
+        ureq::Error::Transport(transport) => format!("Cannot fetch {}", transport),
+    })?;
+
+    let mut text = String::new();
+    response
+        .into_reader()
+        .take(MAX_SUMS_LEN + 1)
+        .read_to_string(&mut text)
+        .map_err(|e| format!("Cannot read {}: {}", url, e))?;
+    if text.len() as u64 > MAX_SUMS_LEN {
+        return Err(format!("{} is over {} MiB; not a checksum file", url, MAX_SUMS_LEN / (1024 * 1024)).into());
+    }
+    Ok(text)
+}
@@ -0,0 +1,227 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fs::File;
+use std::io::{Seek, SeekFrom};
+use dialoguer::{Input, Select};
+
+use crate::algorithms::hash_bytes;
+use crate::records::read_full;
+
+/// Block size offered by default: small enough that a challenge reads little
+/// of the file, large enough that a file has a manageable number of blocks.
+const DEFAULT_BLOCK_SIZE: u64 = 4096;
+
+const DEFAULT_CHALLENGED_BLOCKS: usize = 16;
+
+/// Largest block size a challenge may ask for, so a malformed challenge
+/// can't make the prover allocate without bound.
+const MAX_BLOCK_SIZE: u64 = 16 * 1024 * 1024;
+
+const NONCE_LEN: usize = 16;
+
+/// A request to prove possession of a file: hash these blocks, mixed with
+/// this nonce. Written as `<nonce hex>/<block size>/<index,index,...>`.
+pub struct Challenge {
+    pub nonce: Vec<u8>,
+    pub block_size: u64,
+    /// Block indexes, ascending and distinct.
+    pub blocks: Vec<u64>,
+}
+
+impl fmt::Display for Challenge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let blocks: Vec<String> = self.blocks.iter().map(|index| index.to_string()).collect();
+        write!(f, "{}/{}/{}", hex::encode(&self.nonce), self.block_size, blocks.join(","))
+    }
+}
+
+/// Parses a challenge in the form `Challenge` displays as.
+pub fn parse_challenge(input: &str) -> Result<Challenge, String> {
+    let parts: Vec<&str> = input.trim().split('/').collect();
+    let [nonce, block_size, blocks] = parts[..] else {
+        return Err("expected <nonce hex>/<block size>/<index,index,...>".into());
+    };
+    let nonce = hex::decode(nonce).map_err(|e| format!("nonce: {}", e))?;
+    if nonce.is_empty() {
+        return Err("the nonce is empty".into());
+    }
+    let block_size = match block_size.parse::<u64>() {
+        Ok(size) if size > 0 && size <= MAX_BLOCK_SIZE => size,
+        _ => return Err(format!("'{}' is not a block size from 1 to {}", block_size, MAX_BLOCK_SIZE)),
+    };
+    let blocks = blocks
+        .split(',')
+        .map(|index| index.trim().parse::<u64>().map_err(|_| format!("'{}' is not a block index", index)))
+        .collect::<Result<BTreeSet<u64>, String>>()?;
+    Ok(Challenge { nonce, block_size, blocks: blocks.into_iter().collect() })
+}
+
+/// A fresh challenge for a file of `file_len` bytes: a random nonce and
+/// `count` distinct blocks picked at random (all of them, if the file has no
+/// more than `count`). The verifier must issue it before the file leaves its
+/// hands, or keep the file, to know the right response.
+pub fn new_challenge(file_len: u64, block_size: u64, count: usize) -> Result<Challenge, Box<dyn std::error::Error>> {
+    let random_error = |e: getrandom::Error| format!("Cannot read random bytes from the OS: {}", e);
+    let total = file_len.div_ceil(block_size).max(1);
+    let count = (count as u64).min(total);
+    let mut blocks = BTreeSet::new();
+    while (blocks.len() as u64) < count {
+        // Modulo bias is under total / 2^64: negligible for any real file.
+        blocks.insert(getrandom::u64().map_err(random_error)? % total);
+    }
+    let mut nonce = vec![0u8; NONCE_LEN];
+    getrandom::fill(&mut nonce).map_err(random_error)?;
+    Ok(Challenge { nonce, block_size, blocks: blocks.into_iter().collect() })
+}
+
+/// The prover's answer: SHA-256 of the nonce, then each challenged block's
+/// index (8 bytes, big-endian) and contents, in ascending order. Only the
+/// challenged blocks are read, so the response costs the prover a few reads
+/// and the verifier 32 bytes of traffic instead of the whole file. The
+/// nonce makes every challenge's answer new, so a prover can't keep a table
+/// of answers and throw the file away.
+pub fn respond(path: &str, challenge: &Challenge) -> Result<String, Box<dyn std::error::Error>> {
+    let mut file = File::open(path).map_err(|e| format!("Cannot open '{}': {}", path, e))?;
+    let mut block = vec![0u8; challenge.block_size as usize];
+    let mut preimage = challenge.nonce.clone();
+
+    for &index in &challenge.blocks {
+        let offset = index
+            .checked_mul(challenge.block_size)
+            .ok_or_else(|| format!("block {} is out of range", index))?;
+        file.seek(SeekFrom::Start(offset)).map_err(|e| format!("Cannot seek '{}': {}", path, e))?;
+        let read = read_full(&mut file, &mut block)?;
+        // Block 0 of an empty file is empty; any other block must exist.
+        if read == 0 && index > 0 {
+            return Err(format!("block {} is past the end of '{}'", index, path).into());
+        }
+        preimage.extend_from_slice(&index.to_be_bytes());
+        preimage.extend_from_slice(&block[..read]);
+    }
+    Ok(hash_bytes(&preimage, "SHA-256")?)
+}
+
+/// Proof of retrievability, simplified: the verifier challenges random
+/// blocks, the storage server answers with a hash over them, and the
+/// verifier compares with the answer computed from its own copy (or
+/// precomputed before handing the file over).
+pub fn proof_of_retrievability() {
+    let role_choices = vec![
+        "Verifier: Issue a Challenge",
+        "Prover: Answer a Challenge",
+        "Verifier: Check a Response",
+    ];
+    let role = Select::new()
+        .with_prompt("Proof of retrievability")
+        .items(&role_choices)
+        .default(0)
+        .interact()
+        .unwrap();
+
+    match role {
+        0 => issue_challenge(),
+        1 => answer_challenge(),
+        2 => check_response(),
+        _ => unreachable!(),
+    }
+}
+
+fn read_challenge() -> Challenge {
+    let challenge: String = Input::new()
+        .with_prompt("Challenge")
+        .validate_with(|challenge: &String| parse_challenge(challenge).map(|_| ()))
+        .interact_text()
+        .unwrap();
+    parse_challenge(&challenge).unwrap()
+}
+
+fn issue_challenge() {
+    let path: String = Input::new()
+        .with_prompt("Enter file path (the original)")
+        .interact_text()
+        .unwrap();
+    let block_size: u64 = Input::new()
+        .with_prompt("Block size in bytes")
+        .default(DEFAULT_BLOCK_SIZE)
+        .validate_with(|size: &u64| if *size > 0 && *size <= MAX_BLOCK_SIZE { Ok(()) } else { Err(format!("must be from 1 to {}", MAX_BLOCK_SIZE)) })
+        .interact_text()
+        .unwrap();
+    let count: usize = Input::new()
+        .with_prompt("Blocks to challenge")
+        .default(DEFAULT_CHALLENGED_BLOCKS)
+        .validate_with(|count: &usize| if *count > 0 { Ok(()) } else { Err("must be at least 1") })
+        .interact_text()
+        .unwrap();
+
+    let issued = || -> Result<(u64, Challenge, String), Box<dyn std::error::Error>> {
+        let len = std::fs::metadata(&path).map_err(|e| format!("Cannot read '{}': {}", path, e))?.len();
+        let challenge = new_challenge(len, block_size, count)?;
+        let expected = respond(&path, &challenge)?;
+        Ok((len, challenge, expected))
+    };
+    let (len, challenge, expected) = match issued() {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return;
+        }
+    };
+
+    let total = len.div_ceil(block_size).max(1);
+    let checked = challenge.blocks.len() as f64 / total as f64;
+    println!("\nFile: '{}' ({} bytes, {} blocks)", path, len, total);
+    println!("Challenge: {}", challenge);
+    println!("Expected response: {}\n", expected);
+    println!("Send the challenge to the server and keep the expected response. This");
+    println!("challenge reads {} of {} blocks ({:.1}% of the file); a server that lost", challenge.blocks.len(), total, checked * 100.0);
+    println!("1% of the blocks fails it with probability {:.1}%, and repeated challenges", (1.0 - 0.99f64.powi(challenge.blocks.len() as i32)) * 100.0);
+    println!("drive that up. Each challenge is single-use: once answered, it's public.\n");
+}
+
+fn answer_challenge() {
+    let path: String = Input::new()
+        .with_prompt("Enter file path (the stored copy)")
+        .interact_text()
+        .unwrap();
+    let challenge = read_challenge();
+
+    match respond(&path, &challenge) {
+        Ok(response) => {
+            println!("\nChallenge: {}", challenge);
+            println!("Response: {}\n", response);
+        }
+        Err(e) => eprintln!("Error: {}", e),
+    }
+}
+
+fn check_response() {
+    let challenge = read_challenge();
+    let response: String = Input::new()
+        .with_prompt("Server's response")
+        .interact_text()
+        .unwrap();
+    let expected: String = Input::new()
+        .with_prompt("Expected response, or the original file's path to compute it")
+        .interact_text()
+        .unwrap();
+
+    let expected = match hex::decode(expected.trim()) {
+        Ok(bytes) if bytes.len() == 32 => expected.trim().to_lowercase(),
+        _ => match respond(expected.trim(), &challenge) {
+            Ok(expected) => expected,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return;
+            }
+        },
+    };
+
+    if response.trim().eq_ignore_ascii_case(&expected) {
+        println!("\nPROVEN: the server read every challenged block intact.\n");
+    } else {
+        println!("\nFAILED: the response doesn't match; the server lacks at least one");
+        println!("challenged block as the original had it.");
+        println!("Expected: {}", expected);
+        println!("Got:      {}\n", response.trim());
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+
+use crate::algorithms::AlgorithmSpec;
+
+/// Database used when `--salt-db` isn't given.
+pub const DEFAULT_DB: &str = ".hashing-demo-salts.json";
+
+/// 128 bits, the salt length NIST SP 800-132 asks for at a minimum.
+pub const SALT_LEN: usize = 16;
+
+/// A record's salt, stored next to the digest it produced the way a
+/// password table keeps each user's salt beside their hash.
+#[derive(Serialize, Deserialize)]
+struct SaltRecord {
+    salt: String,
+    algorithm: String,
+    digest: String,
+}
+
+/// The digest of a salted input, and how it relates to the stored record.
+pub struct SaltedHash {
+    pub salt: Vec<u8>,
+    pub digest: String,
+    /// The salt was generated on this run rather than read back.
+    pub fresh: bool,
+    /// The digest previously stored for the record, if it was taken with
+    /// the same salt and algorithm and so can be compared.
+    pub previous: Option<String>,
+}
+
+/// A new random salt from the operating system's CSPRNG (`getrandom`(2) on
+/// Linux, which draws from the same pool as `/dev/urandom`).
+pub fn random_salt() -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut salt = vec![0u8; SALT_LEN];
+    getrandom::fill(&mut salt).map_err(|e| format!("Cannot read random bytes from the OS: {}", e))?;
+    Ok(salt)
+}
+
+/// Hashes `salt || data` with the salt stored for `record` in the JSON
+/// database at `db_path`, generating and storing one on the record's first
+/// use. Reusing the salt keeps a record's digests comparable from run to
+/// run; different records get different salts, so equal inputs under two
+/// records hash differently. `new_salt` discards the stored salt for a
+/// fresh one, after which earlier digests of the record no longer match.
+///
+/// The salt is not secret: it only forces an attacker to attack each record
+/// separately instead of with one precomputed table.
+pub fn salted_hash(
+    record: &str,
+    data: &[u8],
+    db_path: &str,
+    spec: AlgorithmSpec,
+    new_salt: bool,
+) -> Result<SaltedHash, Box<dyn std::error::Error>> {
+    let mut db = read_db(db_path)?;
+    let stored = db.get(record).filter(|_| !new_salt);
+
+    let (salt, fresh) = match stored {
+        Some(stored) => {
+            let salt = hex::decode(&stored.salt)
+                .map_err(|e| format!("salt for '{}' in '{}' is not hex: {}", record, db_path, e))?;
+            (salt, false)
+        }
+        None => (random_salt()?, true),
+    };
+    let previous = stored.filter(|stored| stored.algorithm == spec.to_string()).map(|stored| stored.digest.clone());
+
+    let digest = spec.hash_bytes(&[&salt[..], data].concat())?;
+    db.insert(
+        record.to_string(),
+        SaltRecord { salt: hex::encode(&salt), algorithm: spec.to_string(), digest: digest.clone() },
+    );
+    write_db(db_path, &db)?;
+
+    Ok(SaltedHash { salt, digest, fresh, previous })
+}
+
+fn read_db(db_path: &str) -> Result<BTreeMap<String, SaltRecord>, Box<dyn std::error::Error>> {
+    if !Path::new(db_path).exists() {
+        return Ok(BTreeMap::new());
+    }
+
+    let contents = fs::read_to_string(db_path)
+        .map_err(|e| format!("Cannot read salt database '{}': {}", db_path, e))?;
+    serde_json::from_str(&contents)
+        .map_err(|e| format!("Salt database '{}' is not valid: {}", db_path, e).into())
+}
+
+fn write_db(db_path: &str, db: &BTreeMap<String, SaltRecord>) -> Result<(), Box<dyn std::error::Error>> {
+    let contents = serde_json::to_string_pretty(db)?;
+    fs::write(db_path, contents + "\n")
+        .map_err(|e| format!("Cannot write salt database '{}': {}", db_path, e).into())
+}
@@ -0,0 +1,222 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{self, Path};
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+
+use crate::algorithms::{self, hash_file};
+use crate::manifest::list_files;
+
+/// Database used when `--scrub-db` isn't given.
+pub const DEFAULT_DB: &str = ".hashing-demo-scrub.json";
+
+/// What a file looked like the last time its contents were confirmed good.
+#[derive(Serialize, Deserialize)]
+struct ScrubEntry {
+    algorithm: String,
+    digest: String,
+    size: u64,
+    mtime_ns: u128,
+    /// Unix time of the last scrub that found the digest intact.
+    verified: u64,
+}
+
+/// Outcome for one file in a scrub.
+pub enum ScrubStatus {
+    /// Not in the database yet; its digest was recorded.
+    New,
+    Ok,
+    /// Size or mtime changed, so the new contents are taken as a deliberate
+    /// edit and recorded.
+    Modified,
+    /// The contents changed while size and mtime did not: likely bit-rot.
+    /// The entry keeps the last good digest, so later scrubs flag it too.
+    Corrupt { expected: String, actual: String, verified: u64 },
+    /// In the database but no longer on disk; the entry is dropped.
+    Missing { verified: u64 },
+    /// Could not be stat'ed or read, as a failing sector gives EIO. Any
+    /// entry is left as it was, so the next scrub tries the file again.
+    Unreadable { error: String, verified: Option<u64> },
+}
+
+pub struct ScrubReport {
+    pub files: Vec<(String, ScrubStatus)>,
+}
+
+impl ScrubReport {
+    fn count(&self, matches: fn(&ScrubStatus) -> bool) -> usize {
+        self.files.iter().filter(|(_, status)| matches(status)).count()
+    }
+
+    pub fn corrupt(&self) -> usize {
+        self.count(|status| matches!(status, ScrubStatus::Corrupt { .. }))
+    }
+
+    pub fn unreadable(&self) -> usize {
+        self.count(|status| matches!(status, ScrubStatus::Unreadable { .. }))
+    }
+
+    pub fn print(&self, quiet: bool) {
+        for (file, status) in &self.files {
+            match status {
+                ScrubStatus::New if !quiet => println!("NEW       {}", file),
+                ScrubStatus::Ok if !quiet => println!("OK        {}", file),
+                ScrubStatus::Modified if !quiet => println!("MODIFIED  {}", file),
+                ScrubStatus::Missing { verified } if !quiet => {
+                    println!("MISSING   {} (last verified {})", file, format_time(*verified));
+                }
+                ScrubStatus::Corrupt { expected, actual, verified } => {
+                    println!("CORRUPT   {}", file);
+                    println!("          expected {}", expected);
+                    println!("          actual   {}", actual);
+                    println!("          last verified good {}", format_time(*verified));
+                }
+                ScrubStatus::Unreadable { error, verified } => {
+                    println!("UNREADABLE {}", file);
+                    println!("          {}", error);
+                    match verified {
+                        Some(verified) => println!("          last verified good {}", format_time(*verified)),
+                        None => println!("          never verified"),
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        println!();
+        println!(
+            "Scrubbed {} files: {} ok, {} new, {} modified, {} missing, {} corrupt, {} unreadable",
+            self.files.len() - self.count(|status| matches!(status, ScrubStatus::Missing { .. })),
+            self.count(|status| matches!(status, ScrubStatus::Ok)),
+            self.count(|status| matches!(status, ScrubStatus::New)),
+            self.count(|status| matches!(status, ScrubStatus::Modified)),
+            self.count(|status| matches!(status, ScrubStatus::Missing { .. })),
+            self.corrupt(),
+            self.unreadable(),
+        );
+    }
+}
+
+/// Re-hashes every file under `dir` against the scrub database at `db_path`.
+///
+/// A file whose digest changed while its size and mtime stayed the same was
+/// not rewritten through the file system, which points at silent corruption
+/// on the medium. Files changed the ordinary way are re-recorded as
+/// modified. New files are recorded with `algorithm`; existing entries keep
+/// the algorithm they were recorded with. A file that can't be read is
+/// reported as unreadable and the scrub carries on, so one bad sector
+/// doesn't lose what the rest of the run recorded. Nothing is scheduled
+/// here: run it periodically, e.g. from cron.
+pub fn scrub(dir: &str, db_path: &str, algorithm: &str) -> Result<ScrubReport, Box<dyn std::error::Error>> {
+    let root = Path::new(dir);
+    if !root.is_dir() {
+        return Err(format!("'{}' is not a directory", dir).into());
+    }
+    let algorithm = algorithms::lookup(algorithm)?;
+    let mut db = read_db(db_path)?;
+    let db_key = path::absolute(db_path)?.to_string_lossy().into_owned();
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|now| now.as_secs()).unwrap_or(0);
+
+    let mut files = Vec::new();
+    let mut seen = HashSet::new();
+    for relative in list_files(root)? {
+        let file = root.join(&relative);
+        let key = path::absolute(&file)?.to_string_lossy().into_owned();
+        if key == db_key {
+            continue;
+        }
+        let file = file.to_string_lossy().into_owned();
+
+        let entry_algorithm = match db.get(&key) {
+            Some(entry) => algorithms::lookup(&entry.algorithm).map_err(|e| format!("scrub entry for '{}': {}", file, e))?,
+            None => algorithm,
+        };
+        let read = stat(&file).and_then(|(size, mtime_ns)| {
+            hash_file(&file, entry_algorithm).map(|digest| (size, mtime_ns, digest)).map_err(|e| e.to_string())
+        });
+        let (size, mtime_ns, digest) = match read {
+            Ok(read) => read,
+            Err(error) => {
+                let verified = db.get(&key).map(|entry| entry.verified);
+                seen.insert(key);
+                files.push((relative, ScrubStatus::Unreadable { error, verified }));
+                continue;
+            }
+        };
+
+        let status = match db.get_mut(&key) {
+            None => {
+                db.insert(key.clone(), ScrubEntry { algorithm: algorithm.to_string(), digest, size, mtime_ns, verified: now });
+                ScrubStatus::New
+            }
+            Some(entry) => {
+                if digest == entry.digest {
+                    entry.size = size;
+                    entry.mtime_ns = mtime_ns;
+                    entry.verified = now;
+                    ScrubStatus::Ok
+                } else if entry.size != size || entry.mtime_ns != mtime_ns {
+                    *entry = ScrubEntry { algorithm: entry.algorithm.clone(), digest, size, mtime_ns, verified: now };
+                    ScrubStatus::Modified
+                } else {
+                    ScrubStatus::Corrupt { expected: entry.digest.clone(), actual: digest, verified: entry.verified }
+                }
+            }
+        };
+        seen.insert(key);
+        files.push((relative, status));
+    }
+
+    // Only entries under this directory can be missing; the database may
+    // also track other trees.
+    let prefix = format!("{}{}", path::absolute(root)?.to_string_lossy().trim_end_matches(path::MAIN_SEPARATOR), path::MAIN_SEPARATOR);
+    let missing: Vec<String> = db.keys().filter(|key| key.starts_with(&prefix) && !seen.contains(*key)).cloned().collect();
+    for key in missing {
+        let entry = db.remove(&key).unwrap();
+        files.push((key[prefix.len()..].to_string(), ScrubStatus::Missing { verified: entry.verified }));
+    }
+
+    write_db(db_path, &db)?;
+    Ok(ScrubReport { files })
+}
+
+/// A file's size and modification time in nanoseconds.
+fn stat(file: &str) -> Result<(u64, u128), String> {
+    let metadata = fs::metadata(file).map_err(|e| format!("Cannot stat '{}': {}", file, e))?;
+    let mtime = metadata.modified().map_err(|e| format!("Cannot stat '{}': {}", file, e))?;
+    Ok((metadata.len(), mtime.duration_since(UNIX_EPOCH).map(|mtime| mtime.as_nanos()).unwrap_or(0)))
+}
+
+/// Unix seconds as a UTC date and time, e.g. `2024-03-01 12:00:00 UTC`.
+fn format_time(secs: u64) -> String {
+    // Civil-from-days, from Howard Hinnant's date algorithms.
+    let days = (secs / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    let time = secs % 86400;
+    format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC", year, month, day, time / 3600, time / 60 % 60, time % 60)
+}
+
+fn read_db(db_path: &str) -> Result<BTreeMap<String, ScrubEntry>, Box<dyn std::error::Error>> {
+    if !Path::new(db_path).exists() {
+        return Ok(BTreeMap::new());
+    }
+
+    let contents = fs::read_to_string(db_path)
+        .map_err(|e| format!("Cannot read scrub database '{}': {}", db_path, e))?;
+    serde_json::from_str(&contents)
+        .map_err(|e| format!("Scrub database '{}' is not valid: {}", db_path, e).into())
+}
+
+fn write_db(db_path: &str, db: &BTreeMap<String, ScrubEntry>) -> Result<(), Box<dyn std::error::Error>> {
+    let contents = serde_json::to_string_pretty(db)?;
+    fs::write(db_path, contents + "\n")
+        .map_err(|e| format!("Cannot write scrub database '{}': {}", db_path, e).into())
+}
@@ -0,0 +1,58 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+use tiny_http::{Header, Method, Response, Server};
+
+use crate::cache::{hash_file_cached, HashCache};
+use crate::manifest::list_files;
+
+/// Address `--serve` listens on when `--listen` isn't given: loopback only,
+/// so nothing is exposed until asked for.
+pub const DEFAULT_LISTEN: &str = "127.0.0.1:8080";
+
+/// Serves `GET /manifest.json`, a JSON object mapping each file's path under
+/// `dir` to its current digest. Every request re-reads the tree, so the map
+/// reflects files added, removed or changed since the last one; an in-memory
+/// cache skips re-hashing files whose size and mtime are unchanged.
+///
+/// There is no authentication or TLS: anyone who can reach the address can
+/// list the tree's file names and digests. Bind it to loopback or a trusted
+/// network only.
+pub fn serve_manifest(dir: &str, listen: &str, algorithm: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let root = Path::new(dir);
+    if !root.is_dir() {
+        return Err(format!("'{}' is not a directory", dir).into());
+    }
+    let server = Server::http(listen).map_err(|e| format!("Cannot listen on {}: {}", listen, e))?;
+    let cache = Mutex::new(HashCache::default());
+
+    eprintln!("Serving {} digests of '{}' at http://{}/manifest.json", algorithm, dir, listen);
+    eprintln!("Warning: unauthenticated; expose only to trusted networks. Ctrl-C to stop.");
+
+    for request in server.incoming_requests() {
+        let response = match (request.method(), request.url()) {
+            (Method::Get, "/manifest.json") => match digest_map(root, algorithm, &cache) {
+                Ok(json) => Response::from_string(json)
+                    .with_header(Header::from_bytes("Content-Type", "application/json").unwrap()),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    Response::from_string(format!("{}\n", e)).with_status_code(500)
+                }
+            },
+            (_, "/manifest.json") => Response::from_string("only GET is supported\n").with_status_code(405),
+            _ => Response::from_string("not found; try /manifest.json\n").with_status_code(404),
+        };
+        // A client that hung up is its own problem; keep serving the rest.
+        let _ = request.respond(response);
+    }
+    Ok(())
+}
+
+fn digest_map(root: &Path, algorithm: &str, cache: &Mutex<HashCache>) -> Result<String, Box<dyn std::error::Error>> {
+    let mut digests = BTreeMap::new();
+    for relative in list_files(root)? {
+        let file = root.join(&relative).to_string_lossy().into_owned();
+        digests.insert(relative, hash_file_cached(cache, &file, algorithm)?);
+    }
+    Ok(serde_json::to_string_pretty(&digests)? + "\n")
+}
@@ -0,0 +1,121 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use dialoguer::{Input, Select};
+use hex::encode;
+use siphasher::sip::SipHasher24;
+
+use crate::input::parse_bytes;
+
+/// Buckets in the demo hash table.
+const DEMO_BUCKETS: u64 = 64;
+
+/// Keys the attacker crafts to land in one bucket.
+const DEMO_KEYS: usize = 2000;
+
+/// SipHash-2-4 of `data` under a 128-bit key, the key read as two
+/// little-endian u64 halves as in the reference implementation.
+pub fn siphash24(key: &[u8; 16], data: &[u8]) -> u64 {
+    let mut hasher = SipHasher24::new_with_key(key);
+    hasher.write(data);
+    hasher.finish()
+}
+
+/// A fresh 128-bit key from the standard library's `RandomState`, the same
+/// per-process randomness `HashMap` seeds its own SipHash keys from. Fine for
+/// a hash-table key; not meant for cryptographic keys.
+pub fn random_key() -> [u8; 16] {
+    let mut key = [0u8; 16];
+    for half in key.chunks_exact_mut(8) {
+        half.copy_from_slice(&RandomState::new().build_hasher().finish().to_le_bytes());
+    }
+    key
+}
+
+/// Reads a 16-byte key as text or 0x-prefixed hex, or draws a random one.
+fn read_key() -> [u8; 16] {
+    let key_choices = vec!["Random key", "Enter a key"];
+    let key_source = Select::new()
+        .with_prompt("SipHash key (16 bytes)")
+        .items(&key_choices)
+        .default(0)
+        .interact()
+        .unwrap();
+    if key_source == 0 {
+        return random_key();
+    }
+
+    let key: String = Input::new()
+        .with_prompt("Key (16 bytes of text, or 0x-prefixed hex)")
+        .validate_with(|key: &String| match parse_bytes(key) {
+            Ok(bytes) if bytes.len() == 16 => Ok(()),
+            Ok(bytes) => Err(format!("the key must be 16 bytes, not {}", bytes.len())),
+            Err(e) => Err(e),
+        })
+        .interact_text()
+        .unwrap();
+    parse_bytes(&key).unwrap().try_into().unwrap()
+}
+
+/// SipHash-2-4 is a keyed hash built for hash tables: short inputs, 64-bit
+/// output, fast, and unpredictable without the key. It is a PRF, not a
+/// collision-resistant hash; 64 bits is far too short for fingerprints.
+pub fn siphash_interactive() {
+    let input: String = Input::new()
+        .with_prompt("Enter text to hash")
+        .allow_empty(true)
+        .interact_text()
+        .unwrap();
+    let key = read_key();
+
+    println!("\nInput: '{}'", input);
+    println!("Key: {}", encode(key));
+    println!("SipHash-2-4: {:016x}\n", siphash24(&key, input.as_bytes()));
+    println!("The same input under another key gives an unrelated value, so without the");
+    println!("key nobody can predict where an input lands in a hash table.\n");
+}
+
+/// Hash flooding: with a known hash function, an attacker can search offline
+/// for keys that all fall into one bucket of a server's hash table, turning
+/// every insert and lookup into a walk down one long chain. Here the known
+/// function is SipHash with a fixed all-zero key; the same crafted keys under
+/// a random key, as Rust's `HashMap` picks for every map, spread out evenly.
+pub fn hash_flooding() {
+    let fixed_key = [0u8; 16];
+
+    println!("\nAttacker: searching for {} keys that land in bucket 0 of {}", DEMO_KEYS, DEMO_BUCKETS);
+    println!("when hashed with SipHash-2-4 under the well-known all-zero key...");
+    let mut crafted = Vec::with_capacity(DEMO_KEYS);
+    let mut tried = 0u64;
+    while crafted.len() < DEMO_KEYS {
+        let candidate = format!("user-{}", tried);
+        if siphash24(&fixed_key, candidate.as_bytes()).is_multiple_of(DEMO_BUCKETS) {
+            crafted.push(candidate);
+        }
+        tried += 1;
+    }
+    println!("Found them after trying {} candidates.\n", tried);
+
+    let random = random_key();
+    for (label, key) in [("Fixed all-zero key", fixed_key), ("Random key", random)] {
+        let mut buckets = vec![0u64; DEMO_BUCKETS as usize];
+        // Each insert compares against everything already in its bucket.
+        let mut comparisons = 0u64;
+        for candidate in &crafted {
+            let bucket = &mut buckets[(siphash24(&key, candidate.as_bytes()) % DEMO_BUCKETS) as usize];
+            comparisons += *bucket;
+            *bucket += 1;
+        }
+        let used = buckets.iter().filter(|&&count| count > 0).count();
+
+        println!("{} ({}):", label, encode(key));
+        println!("  Buckets used:       {} of {}", used, DEMO_BUCKETS);
+        println!("  Longest chain:      {}", buckets.iter().max().unwrap());
+        println!("  Insert comparisons: {}", comparisons);
+        println!();
+    }
+
+    println!("Under the known key every crafted key collides and the work grows with the");
+    println!("square of the number of keys. A random key the attacker never sees makes");
+    println!("their precomputed keys ordinary ones again. This is why Rust's HashMap seeds");
+    println!("its hasher (SipHash-1-3) with a random key per map by default.\n");
+}
@@ -0,0 +1,117 @@
+/// Which comment syntaxes the source normalizer strips.
+#[derive(Clone, Copy, Default)]
+pub struct CommentStyles {
+    /// `// to end of line`, as in C, Rust, Go and JavaScript.
+    pub slash: bool,
+    /// `# to end of line`, as in Python, shell and YAML.
+    pub hash: bool,
+    /// `/* block */`, as in C, Rust, CSS and Java. Blocks don't nest.
+    pub block: bool,
+}
+
+/// Comment style names accepted by `parse_comment_styles`, with their syntax.
+pub const COMMENT_STYLES: &[(&str, &str)] = &[("slash", "//"), ("hash", "#"), ("block", "/* */")];
+
+impl CommentStyles {
+    /// The styles' syntaxes, e.g. `//, /* */`, or `none`.
+    pub fn describe(&self) -> String {
+        let enabled = [self.slash, self.hash, self.block];
+        let syntaxes: Vec<&str> = COMMENT_STYLES
+            .iter()
+            .zip(enabled)
+            .filter(|(_, enabled)| *enabled)
+            .map(|((_, syntax), _)| *syntax)
+            .collect();
+        if syntaxes.is_empty() { "none".to_string() } else { syntaxes.join(", ") }
+    }
+}
+
+/// Parses a comma-separated list of comment styles, by name (`slash`,
+/// `hash`, `block`) or by syntax (`//`, `#`, `/*`); `none` strips no
+/// comments and only normalizes whitespace.
+pub fn parse_comment_styles(list: &str) -> Result<CommentStyles, String> {
+    let mut styles = CommentStyles::default();
+    for style in list.split(',').map(str::trim) {
+        match style {
+            "slash" | "//" => styles.slash = true,
+            "hash" | "#" => styles.hash = true,
+            "block" | "/*" | "/* */" => styles.block = true,
+            "none" => {}
+            _ => return Err(format!("unknown comment style '{}'; expected slash (//), hash (#), block (/* */) or none", style)),
+        }
+    }
+    Ok(styles)
+}
+
+/// Identifier and number characters, between which a space is significant
+/// (`let x` is not `letx`). Bytes of multi-byte UTF-8 characters count too,
+/// so non-ASCII identifiers keep their spacing.
+fn is_word(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || byte >= 0x80
+}
+
+/// Rewrites source code so formatting changes don't change it: the chosen
+/// comments are removed, and whitespace is dropped except for a single
+/// space between two identifiers or numbers. String literals in `"`, `'` or
+/// `` ` `` quotes are copied verbatim, comment markers and all.
+///
+/// This is a heuristic, not a parser for any language. It treats code as
+/// equal when a real compiler might not: indentation is dropped, so Python
+/// blocks that differ only in nesting normalize alike, and so do `a - -b` and
+/// `a--b`. A `'` that isn't a quote, like a Rust lifetime, is read as a
+/// literal running to the next `'` or the end of the line. Use it to tell
+/// reformatting from edits, not to prove two programs equivalent.
+pub fn normalize_source(source: &[u8], styles: CommentStyles) -> Vec<u8> {
+    let mut out = Vec::with_capacity(source.len());
+    let mut space = false;
+    let mut i = 0;
+
+    // Separates the previous token from the next one if both are words.
+    let separate = |out: &mut Vec<u8>, space: &mut bool, next: u8| {
+        if *space && out.last().is_some_and(|&last| is_word(last)) && is_word(next) {
+            out.push(b' ');
+        }
+        *space = false;
+    };
+
+    while i < source.len() {
+        let rest = &source[i..];
+        if styles.block && rest.starts_with(b"/*") {
+            i += rest[2..].windows(2).position(|pair| pair == b"*/").map_or(rest.len(), |end| end + 4);
+            space = true;
+        } else if (styles.slash && rest.starts_with(b"//")) || (styles.hash && rest[0] == b'#') {
+            i += rest.iter().position(|&byte| byte == b'\n').unwrap_or(rest.len());
+            space = true;
+        } else if matches!(rest[0], b'"' | b'\'' | b'`') {
+            separate(&mut out, &mut space, rest[0]);
+            let len = literal_len(rest);
+            out.extend_from_slice(&rest[..len]);
+            i += len;
+        } else if rest[0].is_ascii_whitespace() {
+            space = true;
+            i += 1;
+        } else {
+            separate(&mut out, &mut space, rest[0]);
+            out.push(rest[0]);
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Length of the string literal at the start of `rest`, quotes included:
+/// up to the matching unescaped quote, or for `"` and `'` the end of the
+/// line if it is never closed. Backtick literals may span lines.
+fn literal_len(rest: &[u8]) -> usize {
+    let quote = rest[0];
+    let mut i = 1;
+    while i < rest.len() {
+        match rest[i] {
+            b'\\' => i += 2,
+            b'\n' if quote != b'`' => return i,
+            byte if byte == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    rest.len()
+}
@@ -0,0 +1,93 @@
+use std::fs::File;
+use std::io;
+use std::os::unix::fs::FileExt;
+use std::os::unix::io::AsRawFd;
+
+use crate::algorithms::StreamHasher;
+
+const CHUNK_SIZE: u64 = 64 * 1024;
+
+static ZEROS: [u8; CHUNK_SIZE as usize] = [0; CHUNK_SIZE as usize];
+
+/// How a sparse-aware hash got through the file.
+pub struct SparseStats {
+    pub read: u64,
+    pub skipped: u64,
+    /// The file system doesn't support `SEEK_DATA`, so every byte was read.
+    pub fell_back: bool,
+}
+
+/// Hashes a file, using `SEEK_DATA`/`SEEK_HOLE` to find its holes and feeding
+/// the hasher zeros for them instead of reading them from disk. Holes read as
+/// zeros, so the digest is exactly that of a full read; only the I/O is
+/// saved, not the hashing. Where the file system doesn't support the seeks
+/// the whole file is read normally.
+pub fn hash_file_sparse(path: &str, algorithm: &str) -> Result<(String, SparseStats), Box<dyn std::error::Error>> {
+    let file = File::open(path).map_err(|e| format!("Cannot open '{}': {}", path, e))?;
+    let metadata = file.metadata()?;
+    if !metadata.is_file() {
+        return Err(format!("'{}' is not a file", path).into());
+    }
+
+    let len = metadata.len();
+    let mut hasher = StreamHasher::new(algorithm);
+    let mut stats = SparseStats { read: 0, skipped: 0, fell_back: false };
+    let mut offset = 0;
+
+    while offset < len {
+        let data = match seek(&file, offset, libc::SEEK_DATA) {
+            Ok(data) => data.min(len),
+            // No data past here: the rest of the file is one hole.
+            Err(e) if e.raw_os_error() == Some(libc::ENXIO) => len,
+            Err(e) if offset == 0 && e.raw_os_error() == Some(libc::EINVAL) => {
+                stats.fell_back = true;
+                read_range(&file, 0, len, &mut hasher)?;
+                stats.read = len;
+                break;
+            }
+            Err(e) => return Err(format!("Cannot seek in '{}': {}", path, e).into()),
+        };
+
+        feed_zeros(&mut hasher, data - offset);
+        stats.skipped += data - offset;
+        if data == len {
+            break;
+        }
+
+        let hole = seek(&file, data, libc::SEEK_HOLE).map_err(|e| format!("Cannot seek in '{}': {}", path, e))?.min(len);
+        read_range(&file, data, hole, &mut hasher)?;
+        stats.read += hole - data;
+        offset = hole;
+    }
+
+    Ok((hex::encode(hasher.finalize()?), stats))
+}
+
+fn seek(file: &File, offset: u64, whence: libc::c_int) -> io::Result<u64> {
+    // SAFETY: lseek only repositions the descriptor, which `file` keeps open.
+    let position = unsafe { libc::lseek(file.as_raw_fd(), offset as libc::off_t, whence) };
+    if position < 0 { Err(io::Error::last_os_error()) } else { Ok(position as u64) }
+}
+
+fn read_range(file: &File, start: u64, end: u64, hasher: &mut StreamHasher) -> io::Result<()> {
+    let mut buffer = vec![0u8; CHUNK_SIZE as usize];
+    let mut offset = start;
+    while offset < end {
+        let len = (end - offset).min(CHUNK_SIZE) as usize;
+        let read = file.read_at(&mut buffer[..len], offset)?;
+        if read == 0 {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "file shrank while hashing"));
+        }
+        hasher.update(&buffer[..read]);
+        offset += read as u64;
+    }
+    Ok(())
+}
+
+fn feed_zeros(hasher: &mut StreamHasher, mut count: u64) {
+    while count > 0 {
+        let len = count.min(CHUNK_SIZE);
+        hasher.update(&ZEROS[..len as usize]);
+        count -= len;
+    }
+}
@@ -0,0 +1,348 @@
+use dialoguer::{Input, Select};
+use hex::encode;
+use sha2::digest::generic_array::GenericArray;
+use sha2::{Digest, Sha256};
+
+use crate::algorithms::{block_size, hash_bytes, select_algorithm, sha256_padding, StreamHasher, SHA256_IV};
+use crate::count_differences;
+use crate::mac::hmac_sha256;
+
+/// Longer inputs produce more output than anyone will read.
+const MAX_INCREMENTAL_INPUT: usize = 1024;
+
+/// Merkle–Damgård algorithms whose padding the padding demo can lay out.
+/// Both pad the same way and differ only in the byte order of the length.
+const PADDING_ALGORITHMS: &[&str] = &[
+    "SHA-256",
+    #[cfg(feature = "md5")]
+    "MD5",
+];
+
+pub fn teaching_demos() {
+    let demo_choices = vec!["Incremental Hashing", "One Trailing Byte", "Length-Extension Attack", "Message Padding", "Hash Flooding vs SipHash"];
+    let demo_selection = Select::new()
+        .with_prompt("Choose a demo")
+        .items(&demo_choices)
+        .default(0)
+        .interact()
+        .unwrap();
+
+    match demo_selection {
+        0 => incremental_hashing(),
+        1 => trailing_byte(),
+        2 => length_extension(),
+        3 => message_padding(),
+        4 => crate::siphash::hash_flooding(),
+        _ => unreachable!(),
+    }
+}
+
+/// Feeds the input one block at a time and shows what the hasher has after
+/// each block. SHA-256 exposes its chaining value through `compress256`; the
+/// other algorithms only show the running digest snapshot.
+fn incremental_hashing() {
+    let input: String = Input::new()
+        .with_prompt("Enter text to hash")
+        .allow_empty(true)
+        .interact_text()
+        .unwrap();
+
+    if input.len() > MAX_INCREMENTAL_INPUT {
+        eprintln!("Error: input is limited to {} bytes in this mode", MAX_INCREMENTAL_INPUT);
+        return;
+    }
+
+    let algorithm = select_algorithm();
+    let block = block_size(algorithm);
+    let bytes = input.as_bytes();
+    let block_count = bytes.len().div_ceil(block).max(1);
+
+    println!("\nAlgorithm: {}", algorithm);
+    println!("Block size: {} bytes", block);
+    println!("Input length: {} bytes ({} block(s))", bytes.len(), block_count);
+
+    let mut hasher = StreamHasher::new(algorithm);
+    let mut state = SHA256_IV;
+
+    if algorithm == "SHA-256" {
+        println!("\nInitial state: {}", format_state(&state));
+    }
+
+    for (index, chunk) in bytes.chunks(block).enumerate() {
+        hasher.update(chunk);
+
+        println!("\nBlock {}/{} (bytes {}..{}):", index + 1, block_count, index * block, index * block + chunk.len());
+        println!("  Data:     {}", encode(chunk));
+        println!("  Text:     {}", printable(chunk));
+
+        if algorithm == "SHA-256" {
+            if chunk.len() == block {
+                sha2::compress256(&mut state, &[*GenericArray::from_slice(chunk)]);
+                println!("  State:    {}", format_state(&state));
+            } else {
+                println!("  State:    unchanged (partial block is buffered until finalization)");
+            }
+        }
+
+        match hasher.clone().finalize() {
+            Ok(snapshot) => println!("  Snapshot: {}", encode(snapshot)),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return;
+            }
+        }
+    }
+
+    match hasher.finalize() {
+        Ok(digest) => println!("\nFinal digest: {}", encode(digest)),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return;
+        }
+    }
+    println!("Each snapshot is the digest of the input up to and including that block.");
+    if algorithm == "SHA-256" {
+        println!("The state is the chaining value after each full block; finalization pads the");
+        println!("buffered tail and compresses it once more to produce the digest.");
+    }
+    println!();
+}
+
+/// Hashes the input with and without one extra byte on the end. The two
+/// digests share nothing visible, so a digest can't be trimmed or extended to
+/// match a slightly different input; the usual culprit in practice is a
+/// trailing newline added by an editor or `echo`.
+fn trailing_byte() {
+    let input: String = Input::new()
+        .with_prompt("Enter text to hash")
+        .allow_empty(true)
+        .interact_text()
+        .unwrap();
+
+    let extra: String = Input::new()
+        .with_prompt("Byte to append, in hex")
+        .default("0a".to_string())
+        .validate_with(|byte: &String| match u8::from_str_radix(byte.strip_prefix("0x").unwrap_or(byte), 16) {
+            Ok(_) => Ok(()),
+            Err(_) => Err("enter a single byte, 00 to ff"),
+        })
+        .interact_text()
+        .unwrap();
+    let extra = u8::from_str_radix(extra.strip_prefix("0x").unwrap_or(&extra), 16).unwrap();
+
+    let algorithm = select_algorithm();
+    let mut extended = input.as_bytes().to_vec();
+    extended.push(extra);
+
+    let (hash1, hash2) = match (hash_bytes(input.as_bytes(), algorithm), hash_bytes(&extended, algorithm)) {
+        (Ok(hash1), Ok(hash2)) => (hash1, hash2),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("Error: {}", e);
+            return;
+        }
+    };
+    let differences = count_differences(&hash1, &hash2);
+
+    println!("\nAlgorithm: {}", algorithm);
+    println!("Input:          {:?}", input);
+    println!("Input + 0x{:02x}:   {:?}", extra, String::from_utf8_lossy(&extended));
+    println!();
+    println!("Hash:           {}", hash1);
+    println!("Hash + 0x{:02x}:    {}", extra, hash2);
+    println!();
+    println!("{} of {} hex digits differ; unrelated digests differ in about 15 of every 16.", differences, hash1.len());
+    println!("Nothing in the first digest hints at the second, or the other way round.\n");
+}
+
+/// Forges a SHA-256 "MAC" of the form `H(key || message)` without the key.
+///
+/// SHA-256's digest is its whole internal state after the padded input, so
+/// anyone holding `H(key || message)` can load it back into the compression
+/// function and keep hashing: the result is the valid digest of
+/// `key || message || padding || extension`. The attacker needs the key's
+/// length, which is easily guessed, but never the key itself. HMAC and
+/// `H(H(key || message))` both hide that state behind a second hash, and the
+/// same forgery fails against them.
+fn length_extension() {
+    let key: String = Input::new()
+        .with_prompt("Secret key (known only to the server)")
+        .default("s3cret-key".to_string())
+        .interact_text()
+        .unwrap();
+    let message: String = Input::new()
+        .with_prompt("Message the server signs")
+        .default("user=alice&role=user".to_string())
+        .interact_text()
+        .unwrap();
+    let extension: String = Input::new()
+        .with_prompt("Data the attacker appends")
+        .default("&role=admin".to_string())
+        .interact_text()
+        .unwrap();
+
+    let signed = [key.as_bytes(), message.as_bytes()].concat();
+    let tag: [u8; 32] = Sha256::digest(&signed).into();
+
+    // The attacker's side: only the message, its tag and the key length.
+    let glue = sha256_padding(signed.len() as u64);
+    let forged_message = [message.as_bytes(), &glue, extension.as_bytes()].concat();
+    let forged_tag = sha256_extend(&tag, (signed.len() + glue.len()) as u64, extension.as_bytes());
+
+    // The server's side: recompute over the forged message with the key.
+    let forged_signed = [key.as_bytes(), &forged_message].concat();
+    let actual: [u8; 32] = Sha256::digest(&forged_signed).into();
+
+    println!("\nServer signs SHA-256(key || message):");
+    println!("  Message: {:?}", message);
+    println!("  Tag:     {}", encode(tag));
+    println!();
+    println!("Attacker, knowing the tag and that the key is {} bytes:", key.len());
+    println!("  Glue padding:   {}", encode(&glue));
+    println!("  Forged message: {}", printable(&forged_message));
+    println!("  Forged tag:     {}", encode(forged_tag));
+    println!();
+    println!("Server checks SHA-256(key || forged message):");
+    println!("  Expected tag:   {}", encode(actual));
+    if actual == forged_tag {
+        println!("  ACCEPTED: the forgery verifies, and the key was never used to make it.");
+    } else {
+        println!("  REJECTED");
+    }
+    println!();
+
+    let hmac_tag = hmac_sha256(key.as_bytes(), &forged_message);
+    let double: [u8; 32] = Sha256::digest(Sha256::digest(&forged_signed)).into();
+    println!("The same forged tag against the mitigations:");
+    println!("  HMAC-SHA256(key, forged message): {} {}", encode(hmac_tag), verdict(hmac_tag == forged_tag));
+    println!("  SHA-256(SHA-256(key || forged)):  {} {}", encode(double), verdict(double == forged_tag));
+    println!();
+    println!("Both hash the inner result again, so the published tag no longer exposes the");
+    println!("state an attacker would need to continue from. Use HMAC for keyed hashes.\n");
+}
+
+/// Shows the padded input block by block, the way the compression function
+/// sees it: the message, a single 0x80 byte, zeros up to 56 mod 64, and the
+/// message length in bits as a u64 (big-endian for SHA-256, little-endian
+/// for MD5).
+fn message_padding() {
+    let input: String = Input::new()
+        .with_prompt("Enter text to hash")
+        .allow_empty(true)
+        .interact_text()
+        .unwrap();
+
+    if input.len() > MAX_INCREMENTAL_INPUT {
+        eprintln!("Error: input is limited to {} bytes in this mode", MAX_INCREMENTAL_INPUT);
+        return;
+    }
+
+    let selection = Select::new()
+        .with_prompt("Choose an algorithm")
+        .items(PADDING_ALGORITHMS)
+        .default(0)
+        .interact()
+        .unwrap();
+    let algorithm = PADDING_ALGORITHMS[selection];
+
+    let bytes = input.as_bytes();
+    let len = bytes.len() as u64;
+    let padding = match algorithm {
+        "SHA-256" => sha256_padding(len),
+        _ => md5_padding(len),
+    };
+    let padded = [bytes, &padding].concat();
+    let zeros = padding.len() - 9;
+    let byte_order = if algorithm == "SHA-256" { "big" } else { "little" };
+
+    println!("\nAlgorithm: {}", algorithm);
+    println!("Input length: {} bytes = {} bits", len, len * 8);
+    println!("Padding: 0x80, {} zero byte(s), then {} as a {}-endian u64", zeros, len * 8, byte_order);
+    println!("Padded length: {} bytes ({} block(s) of 64)", padded.len(), padded.len() / 64);
+    println!("Key: M message, P the 0x80 byte, 0 zero fill, L length");
+
+    for (index, block) in padded.chunks_exact(64).enumerate() {
+        println!("\nBlock {}:", index + 1);
+        for (row, chunk) in block.chunks(16).enumerate() {
+            let start = index * 64 + row * 16;
+            let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+            let key: Vec<&str> = (start..start + chunk.len())
+                .map(|offset| padding_role(offset, bytes.len(), padded.len()))
+                .collect();
+            println!("  {:04x}  {}", start, hex.join(" "));
+            println!("        {}", key.join(" "));
+        }
+    }
+
+    match hash_bytes(bytes, algorithm) {
+        Ok(digest) => println!("\nDigest: {}", digest),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return;
+        }
+    }
+    if algorithm == "SHA-256" {
+        let mut state = SHA256_IV;
+        for block in padded.chunks_exact(64) {
+            sha2::compress256(&mut state, &[*GenericArray::from_slice(block)]);
+        }
+        println!("Compressing the blocks above from the IV: {}", format_state(&state).replace(' ', ""));
+    }
+    println!("The compression function only ever sees these blocks. The 0x80 marks where the");
+    println!("message ends and the length closes the last block, so no two inputs pad alike.\n");
+}
+
+/// What a byte of the padded input is, as a two-column label.
+fn padding_role(offset: usize, message_len: usize, padded_len: usize) -> &'static str {
+    if offset < message_len {
+        " M"
+    } else if offset == message_len {
+        " P"
+    } else if offset < padded_len - 8 {
+        " 0"
+    } else {
+        " L"
+    }
+}
+
+fn verdict(accepted: bool) -> &'static str {
+    if accepted { "ACCEPTED" } else { "rejected" }
+}
+
+/// MD5 pads like SHA-256 but appends the bit length little-endian.
+fn md5_padding(len: u64) -> Vec<u8> {
+    let mut padding = sha256_padding(len);
+    let length = padding.len() - 8;
+    padding[length..].copy_from_slice(&(len * 8).to_le_bytes());
+    padding
+}
+
+/// Continues SHA-256 from a published digest, as if `extension` had followed
+/// the `prior_len` bytes (padding included) that produced it.
+fn sha256_extend(digest: &[u8; 32], prior_len: u64, extension: &[u8]) -> [u8; 32] {
+    let mut state = [0u32; 8];
+    for (word, bytes) in state.iter_mut().zip(digest.chunks_exact(4)) {
+        *word = u32::from_be_bytes(bytes.try_into().unwrap());
+    }
+
+    let tail = [extension, &sha256_padding(prior_len + extension.len() as u64)].concat();
+    for block in tail.chunks_exact(64) {
+        sha2::compress256(&mut state, &[*GenericArray::from_slice(block)]);
+    }
+
+    let mut output = [0u8; 32];
+    for (bytes, word) in output.chunks_exact_mut(4).zip(state) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+    output
+}
+
+fn format_state(state: &[u32; 8]) -> String {
+    state.iter().map(|word| format!("{:08x}", word)).collect::<Vec<_>>().join(" ")
+}
+
+fn printable(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' })
+        .collect()
+}
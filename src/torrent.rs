@@ -0,0 +1,141 @@
+use std::fs::File;
+use dialoguer::Input;
+use hex::encode;
+use sha2::{Digest, Sha256};
+
+use crate::algorithms::check_file;
+use crate::records::read_full;
+
+/// BEP 52 fixes the leaf block at 16 KiB; only the last block of a file may
+/// be shorter, and it is hashed as is, without padding.
+pub const BLOCK_SIZE: usize = 16 * 1024;
+
+/// Piece lengths get unwieldy past this; BEP 52 sets no upper bound, but
+/// clients commonly refuse anything larger.
+const MAX_PIECE_LENGTH: usize = 64 * 1024 * 1024;
+
+/// Piece layer hashes listed before the rest are summarized.
+const PIECE_LAYER_SHOWN: usize = 16;
+
+/// The per-file Merkle tree of a v2 torrent (BEP 52).
+pub struct FileTree {
+    pub size: u64,
+    pub blocks: usize,
+    /// The `pieces root` of the file's entry in the `file tree`.
+    pub root: [u8; 32],
+    /// The file's entry in `piece layers`, one hash per piece. BEP 52 only
+    /// stores it for files larger than one piece, so it is empty otherwise.
+    pub piece_layer: Vec<[u8; 32]>,
+}
+
+pub fn torrent_v2_interactive() {
+    let path: String = Input::new()
+        .with_prompt("Enter file path")
+        .interact_text()
+        .unwrap();
+    let piece_kib: usize = Input::new()
+        .with_prompt("Piece length in KiB (a power of two, at least 16)")
+        .default(256)
+        .validate_with(|kib: &usize| check_piece_length(kib.saturating_mul(1024)))
+        .interact_text()
+        .unwrap();
+
+    match file_tree(&path, piece_kib * 1024) {
+        Ok(None) => {
+            println!("\nFile: '{}' is empty", path);
+            println!("BEP 52 gives empty files no pieces root; their file tree entry only has a length.\n");
+        }
+        Ok(Some(tree)) => {
+            println!("\nFile: '{}'", path);
+            println!("Size: {} bytes, {} blocks of {} KiB", tree.size, tree.blocks, BLOCK_SIZE / 1024);
+            println!("Tree: {} leaves after padding to a power of two", tree.blocks.next_power_of_two());
+            println!("Pieces Root: {}", encode(tree.root));
+            println!();
+            if tree.piece_layer.is_empty() {
+                println!("The file fits in one {} KiB piece, so it has no piece layer.\n", piece_kib);
+            } else {
+                println!("Piece Layer ({} hashes of {} KiB pieces):", tree.piece_layer.len(), piece_kib);
+                for (index, hash) in tree.piece_layer.iter().enumerate().take(PIECE_LAYER_SHOWN) {
+                    println!("  {:>6}  {}", index, encode(hash));
+                }
+                if tree.piece_layer.len() > PIECE_LAYER_SHOWN {
+                    println!("  ... {} more", tree.piece_layer.len() - PIECE_LAYER_SHOWN);
+                }
+                println!();
+            }
+            println!("Leaves are SHA-256 of 16 KiB blocks, padded with zero hashes; each node is");
+            println!("SHA-256(left || right).\n");
+        }
+        Err(e) => eprintln!("Error: {}", e),
+    }
+}
+
+/// BEP 52: "piece length ... must be a power of two and at least 16KiB".
+pub fn check_piece_length(piece_length: usize) -> Result<(), String> {
+    if piece_length < BLOCK_SIZE || !piece_length.is_power_of_two() {
+        return Err(format!("piece length must be a power of two of at least {} KiB", BLOCK_SIZE / 1024));
+    }
+    if piece_length > MAX_PIECE_LENGTH {
+        return Err(format!("piece length above {} MiB is not supported", MAX_PIECE_LENGTH / (1024 * 1024)));
+    }
+    Ok(())
+}
+
+/// Builds the Merkle tree BEP 52 defines for one file: SHA-256 of each
+/// 16 KiB block as the leaves, padded with all-zero hashes up to a power of
+/// two, each parent the SHA-256 of its two children concatenated. The piece
+/// layer is the level of the tree whose nodes each cover `piece_length`
+/// bytes, cut off after the last piece that holds file data. Returns `None`
+/// for an empty file, which has no tree.
+pub fn file_tree(path: &str, piece_length: usize) -> Result<Option<FileTree>, Box<dyn std::error::Error>> {
+    check_piece_length(piece_length)?;
+    check_file(path)?;
+
+    let mut file = File::open(path).map_err(|e| format!("Cannot open '{}': {}", path, e))?;
+    let mut buffer = vec![0u8; BLOCK_SIZE];
+    let mut leaves = Vec::new();
+    let mut size = 0u64;
+    loop {
+        let read = read_full(&mut file, &mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        leaves.push(<[u8; 32]>::from(Sha256::digest(&buffer[..read])));
+        size += read as u64;
+        if read < BLOCK_SIZE {
+            break;
+        }
+    }
+    if leaves.is_empty() {
+        return Ok(None);
+    }
+
+    let blocks = leaves.len();
+    let blocks_per_piece = piece_length / BLOCK_SIZE;
+    let pieces = size.div_ceil(piece_length as u64) as usize;
+
+    let mut level = leaves;
+    level.resize(blocks.next_power_of_two(), [0u8; 32]);
+    let mut width = 1;
+    let mut piece_layer = Vec::new();
+    loop {
+        if width == blocks_per_piece && pieces > 1 {
+            piece_layer = level[..pieces].to_vec();
+        }
+        if level.len() == 1 {
+            break;
+        }
+        level = level
+            .chunks_exact(2)
+            .map(|pair| {
+                let mut hasher = Sha256::new();
+                hasher.update(pair[0]);
+                hasher.update(pair[1]);
+                hasher.finalize().into()
+            })
+            .collect();
+        width *= 2;
+    }
+
+    Ok(Some(FileTree { size, blocks, root: level[0], piece_layer }))
+}
@@ -0,0 +1,178 @@
+use dialoguer::{Input, MultiSelect, Select};
+use hex::encode;
+
+use crate::source::{normalize_source, CommentStyles, COMMENT_STYLES};
+
+pub const TRANSFORMS: &[&str] = &[
+    "None",
+    "XOR with Repeating Key",
+    "Prepend CompactSize Length (Bitcoin)",
+    "Case-Fold Text (lossy)",
+    "Normalize Source Code (heuristic)",
+];
+
+/// A pre-processing step applied to the input bytes before they reach the
+/// hasher.
+pub enum Transform {
+    /// XOR every byte with a key repeated over the whole input, as used by
+    /// some formats to lightly obfuscate their payload.
+    Xor(Vec<u8>),
+    /// Prefix the input with its length as a Bitcoin CompactSize integer, the
+    /// way byte strings are serialized on the wire and inside transactions.
+    CompactSizePrefix,
+    /// Lowercase text so inputs differing only in case hash the same. This
+    /// is lossy: the digest no longer tells "Hello" from "hello".
+    ///
+    /// Valid UTF-8 is mapped with Rust's `str::to_lowercase`, the Unicode
+    /// default lowercase mapping including the context-sensitive final sigma.
+    /// That is simpler than full Unicode case folding: "ß" stays "ß" rather
+    /// than becoming "ss", so "STRASSE" and "straße" still differ, and no
+    /// normalization is applied, so composed and decomposed accents differ
+    /// too. Input that isn't UTF-8 only has its ASCII letters lowercased.
+    CaseFold,
+    /// Strip comments of the given styles and insignificant whitespace from
+    /// source code, so reformatting alone doesn't change the digest. See
+    /// `normalize_source` for what the heuristic gets wrong.
+    NormalizeSource(CommentStyles),
+}
+
+impl Transform {
+    pub fn apply(&self, mut data: Vec<u8>) -> Vec<u8> {
+        match self {
+            Transform::Xor(key) => {
+                for (byte, k) in data.iter_mut().zip(key.iter().cycle()) {
+                    *byte ^= k;
+                }
+                data
+            }
+            Transform::CaseFold => match String::from_utf8(data) {
+                Ok(text) => text.to_lowercase().into_bytes(),
+                Err(e) => {
+                    let mut bytes = e.into_bytes();
+                    bytes.make_ascii_lowercase();
+                    bytes
+                }
+            },
+            Transform::NormalizeSource(styles) => normalize_source(&data, *styles),
+            Transform::CompactSizePrefix => {
+                let mut prefixed = compact_size(data.len() as u64);
+                prefixed.extend_from_slice(&data);
+                prefixed
+            }
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Transform::Xor(_) => TRANSFORMS[1],
+            Transform::CompactSizePrefix => TRANSFORMS[2],
+            Transform::CaseFold => TRANSFORMS[3],
+            Transform::NormalizeSource(_) => TRANSFORMS[4],
+        }
+    }
+
+    /// A one-line description for output, given the length of the input the
+    /// transform is applied to.
+    pub fn describe(&self, input_len: u64) -> String {
+        match self {
+            Transform::Xor(key) => format!("XOR with repeating key 0x{}", encode(key)),
+            Transform::CompactSizePrefix => {
+                format!("CompactSize length prefix 0x{} ({} bytes)", encode(compact_size(input_len)), input_len)
+            }
+            Transform::CaseFold => "case-folded to lowercase (lossy: case differences are ignored)".to_string(),
+            Transform::NormalizeSource(styles) => format!(
+                "source normalized: comments ({}) and whitespace removed (a heuristic, not a parser)",
+                styles.describe()
+            ),
+        }
+    }
+}
+
+/// Bitcoin's CompactSize encoding: values below 0xfd are a single byte,
+/// larger ones are a marker byte (0xfd, 0xfe or 0xff) followed by the value
+/// as a little-endian u16, u32 or u64.
+pub fn compact_size(value: u64) -> Vec<u8> {
+    match value {
+        0..=0xfc => vec![value as u8],
+        0xfd..=0xffff => [&[0xfd][..], &(value as u16).to_le_bytes()].concat(),
+        0x1_0000..=0xffff_ffff => [&[0xfe][..], &(value as u32).to_le_bytes()].concat(),
+        _ => [&[0xff][..], &value.to_le_bytes()].concat(),
+    }
+}
+
+pub fn parse_xor_key(key: &str) -> Result<Transform, String> {
+    let key = key.strip_prefix("0x").unwrap_or(key);
+    match hex::decode(key) {
+        Ok(bytes) if !bytes.is_empty() => Ok(Transform::Xor(bytes)),
+        Ok(_) => Err("XOR key must not be empty".into()),
+        Err(e) => Err(format!("XOR key is not valid hex: {}", e)),
+    }
+}
+
+pub fn select_transform() -> Option<Transform> {
+    let selection = Select::new()
+        .with_prompt("Choose a pre-hash transformation")
+        .items(TRANSFORMS)
+        .default(0)
+        .interact()
+        .unwrap();
+
+    match selection {
+        0 => None,
+        1 => {
+            let key: String = Input::new()
+                .with_prompt("XOR key in hex")
+                .validate_with(|key: &String| parse_xor_key(key).map(|_| ()))
+                .interact_text()
+                .unwrap();
+            parse_xor_key(&key).ok()
+        }
+        2 => Some(Transform::CompactSizePrefix),
+        3 => Some(Transform::CaseFold),
+        4 => {
+            let labels: Vec<String> = COMMENT_STYLES.iter().map(|(name, syntax)| format!("{} ({})", syntax, name)).collect();
+            let chosen = MultiSelect::new()
+                .with_prompt("Comment styles to strip (space to toggle)")
+                .items(&labels)
+                .interact()
+                .unwrap();
+            Some(Transform::NormalizeSource(CommentStyles {
+                slash: chosen.contains(&0),
+                hash: chosen.contains(&1),
+                block: chosen.contains(&2),
+            }))
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::{digest_bytes, hash_bytes};
+
+    #[test]
+    fn compact_size_prefix_boundaries() {
+        for (len, prefix) in [
+            (0, &[0x00][..]),
+            (0xfc, &[0xfc]),
+            (0xfd, &[0xfd, 0xfd, 0x00]),
+            (0xffff, &[0xfd, 0xff, 0xff]),
+            (0x1_0000, &[0xfe, 0x00, 0x00, 0x01, 0x00]),
+        ] {
+            let prefixed = Transform::CompactSizePrefix.apply(vec![0xaa; len]);
+            assert_eq!(&prefixed[..prefix.len()], prefix, "length 0x{:x}", len);
+            assert_eq!(prefixed.len(), prefix.len() + len, "length 0x{:x}", len);
+        }
+        assert_eq!(compact_size(0xffff_ffff), [0xfe, 0xff, 0xff, 0xff, 0xff]);
+        assert_eq!(compact_size(0x1_0000_0000), [0xff, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn compact_size_prefixed_double_sha256() {
+        let prefixed = Transform::CompactSizePrefix.apply(b"hello".to_vec());
+        assert_eq!(prefixed, b"\x05hello");
+        let first = digest_bytes(&prefixed, "SHA-256").unwrap();
+        assert_eq!(hash_bytes(&first, "SHA-256").unwrap(), "838a3751cb737a28373d14271bd041c6632f74d8be677e02a7b1969190113683");
+    }
+}
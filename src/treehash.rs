@@ -0,0 +1,201 @@
+use std::fs;
+use std::thread;
+use std::time::Instant;
+use blake3::hazmat::{left_subtree_len, merge_subtrees_non_root, merge_subtrees_root, ChainingValue, HasherExt, Mode};
+use blake3::CHUNK_LEN;
+use clap::ValueEnum;
+use dialoguer::{Input, Select};
+use hex::encode;
+use sha2::{Digest, Sha256};
+
+use crate::merkle::{leaf_hash, node_hash};
+use crate::torrent::BLOCK_SIZE;
+
+/// A hash built as a tree over independent leaves, so the leaves can be
+/// hashed on different threads and only the small interior left to combine.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum TreeKind {
+    /// BLAKE3: 1 KiB chunks under a binary tree whose left subtrees are
+    /// always complete powers of two.
+    Blake3,
+    /// The `pieces root` of BitTorrent v2 (BEP 52): SHA-256 of 16 KiB
+    /// blocks, padded with zero hashes to a power of two.
+    #[value(name = "bittorrent-v2")]
+    BitTorrentV2,
+    /// An RFC 6962 Merkle tree, as Certificate Transparency logs use, over
+    /// 16 KiB leaves.
+    Rfc6962,
+}
+
+pub const TREE_KINDS: &[&str] = &["BLAKE3", "BitTorrent v2 (BEP 52)", "RFC 6962 Merkle"];
+
+impl TreeKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TreeKind::Blake3 => TREE_KINDS[0],
+            TreeKind::BitTorrentV2 => TREE_KINDS[1],
+            TreeKind::Rfc6962 => TREE_KINDS[2],
+        }
+    }
+
+    /// The tree's root over `data`, with leaves spread over up to `threads`
+    /// threads. The tree's shape depends only on the data's length, never on
+    /// the thread count, so every count gives the same root.
+    pub fn root(&self, data: &[u8], threads: usize) -> Result<[u8; 32], String> {
+        match self {
+            TreeKind::Blake3 => Ok(blake3_root(data, threads)),
+            TreeKind::BitTorrentV2 => {
+                if data.is_empty() {
+                    return Err("BEP 52 gives an empty file no pieces root".to_string());
+                }
+                let mut level = parallel_leaves(data, threads, |block| Sha256::digest(block).into());
+                level.resize(level.len().next_power_of_two(), [0u8; 32]);
+                while level.len() > 1 {
+                    level = level.chunks_exact(2).map(|pair| Sha256::new().chain_update(pair[0]).chain_update(pair[1]).finalize().into()).collect();
+                }
+                Ok(level[0])
+            }
+            TreeKind::Rfc6962 => Ok(rfc6962_root(&parallel_leaves(data, threads, leaf_hash))),
+        }
+    }
+}
+
+/// Hashes the data's `BLOCK_SIZE` leaves, each thread taking one contiguous
+/// run of them. The interior nodes left over cost one short hash per leaf,
+/// against hashing a whole block for each leaf, so they stay on one thread.
+fn parallel_leaves(data: &[u8], threads: usize, leaf: fn(&[u8]) -> [u8; 32]) -> Vec<[u8; 32]> {
+    let blocks: Vec<&[u8]> = data.chunks(BLOCK_SIZE).collect();
+    let per_thread = blocks.len().div_ceil(threads.max(1)).max(1);
+    thread::scope(|scope| {
+        let handles: Vec<_> = blocks
+            .chunks(per_thread)
+            .map(|run| scope.spawn(move || run.iter().map(|block| leaf(block)).collect::<Vec<_>>()))
+            .collect();
+        handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+    })
+}
+
+/// RFC 6962 section 2.1: the tree of n leaves is the tree of the first k,
+/// k the largest power of two below n, joined to the tree of the rest. An
+/// empty tree's root is SHA-256 of nothing.
+fn rfc6962_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    match leaves.len() {
+        0 => Sha256::digest([]).into(),
+        1 => leaves[0],
+        n => {
+            let k = 1 << (n - 1).ilog2();
+            node_hash(&rfc6962_root(&leaves[..k]), &rfc6962_root(&leaves[k..]))
+        }
+    }
+}
+
+/// BLAKE3 split at the same subtree boundaries its tree already has: the
+/// input is halved where BLAKE3 puts the root's left subtree, each half
+/// hashed on its own share of the threads, and the two chaining values
+/// merged into the root. On one thread, or input of one chunk, it is just
+/// `blake3::hash`.
+fn blake3_root(data: &[u8], threads: usize) -> [u8; 32] {
+    if threads <= 1 || data.len() <= CHUNK_LEN {
+        return blake3::hash(data).into();
+    }
+    let (left, right) = blake3_halves(data, 0, threads);
+    merge_subtrees_root(&left, &right, Mode::Hash).into()
+}
+
+/// The chaining value of the subtree of `data`, which starts `offset` bytes
+/// into the whole input.
+fn blake3_subtree(data: &[u8], offset: u64, threads: usize) -> ChainingValue {
+    if threads <= 1 || data.len() <= CHUNK_LEN {
+        return blake3::Hasher::new().set_input_offset(offset).update(data).finalize_non_root();
+    }
+    let (left, right) = blake3_halves(data, offset, threads);
+    merge_subtrees_non_root(&left, &right, Mode::Hash)
+}
+
+/// Chaining values of the two children of the subtree of `data`, the left
+/// (the larger half) hashed on a new thread.
+fn blake3_halves(data: &[u8], offset: u64, threads: usize) -> (ChainingValue, ChainingValue) {
+    let split = left_subtree_len(data.len() as u64);
+    let (left, right) = data.split_at(split as usize);
+    let left_threads = threads - threads / 2;
+    thread::scope(|scope| {
+        let left = scope.spawn(move || blake3_subtree(left, offset, left_threads));
+        let right = blake3_subtree(right, offset + split, threads / 2);
+        (left.join().unwrap(), right)
+    })
+}
+
+/// Threads used when none are asked for: one per available core.
+pub fn default_threads() -> usize {
+    thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+}
+
+/// Hashes a file's tree on one thread and on `threads`, and sequential
+/// SHA-256 for contrast, over the same in-memory copy so only hashing is
+/// timed. Fails if the roots differ, which would be a bug.
+pub fn tree_hash_report(path: &str, kind: TreeKind, threads: usize) -> Result<(), Box<dyn std::error::Error>> {
+    let data = fs::read(path).map_err(|e| format!("Cannot read '{}': {}", path, e))?;
+
+    let start = Instant::now();
+    let single = kind.root(&data, 1)?;
+    let single_secs = start.elapsed().as_secs_f64().max(f64::EPSILON);
+
+    let start = Instant::now();
+    let parallel = kind.root(&data, threads)?;
+    let parallel_secs = start.elapsed().as_secs_f64().max(f64::EPSILON);
+
+    if single != parallel {
+        return Err(format!("{} root on {} threads differs from the single-threaded root", kind.name(), threads).into());
+    }
+
+    let start = Instant::now();
+    std::hint::black_box(Sha256::digest(&data));
+    let sha256_secs = start.elapsed().as_secs_f64().max(f64::EPSILON);
+
+    let mb = data.len() as f64 / 1_000_000.0;
+    println!("File: '{}' ({} bytes)", path, data.len());
+    println!("Tree: {}", kind.name());
+    println!("Root: {}", encode(single));
+    println!();
+    let label = format!("{} thread{}:", threads, if threads == 1 { "" } else { "s" });
+    println!("{:<12}{:.3} s ({:.1} MB/s)", "1 thread:", single_secs, mb / single_secs);
+    println!("{:<12}{:.3} s ({:.1} MB/s), same root", label, parallel_secs, mb / parallel_secs);
+    println!("{:<12}{:.2}x", "Speedup:", single_secs / parallel_secs);
+    println!("{:<12}{:.3} s ({:.1} MB/s), sequential whatever the threads", "SHA-256:", sha256_secs, mb / sha256_secs);
+    Ok(())
+}
+
+/// SHA-256 and other Merkle–Damgård hashes chain every block through the
+/// one before it, so a single digest can only ever use one core. A tree
+/// hash's leaves don't depend on each other, so they can be hashed on as
+/// many threads as there are, and only the short hashes of the interior
+/// nodes remain to combine.
+pub fn tree_hash_interactive() {
+    let path: String = Input::new()
+        .with_prompt("Enter file path")
+        .interact_text()
+        .unwrap();
+    let selection = Select::new()
+        .with_prompt("Tree hash")
+        .items(TREE_KINDS)
+        .default(0)
+        .interact()
+        .unwrap();
+    let kind = TreeKind::value_variants()[selection];
+    let threads: usize = Input::new()
+        .with_prompt("Threads")
+        .default(default_threads())
+        .validate_with(|threads: &usize| if *threads > 0 { Ok(()) } else { Err("at least 1 thread") })
+        .interact_text()
+        .unwrap();
+
+    println!();
+    match tree_hash_report(path.trim(), kind, threads) {
+        Ok(()) => {
+            println!();
+            println!("Small files gain little: starting threads costs more than the leaves they");
+            println!("share out.\n");
+        }
+        Err(e) => eprintln!("Error: {}", e),
+    }
+}
@@ -0,0 +1,91 @@
+use dialoguer::{Input, Select};
+use hex::encode;
+use sha1::{Digest, Sha1};
+use uuid::{Builder, Uuid};
+
+/// The namespaces RFC 9562 (formerly RFC 4122) defines in its Appendix C,
+/// for names that are DNS names, URLs, ISO OIDs and X.500 DNs.
+pub const NAMESPACES: &[(&str, Uuid)] = &[
+    ("DNS", Uuid::NAMESPACE_DNS),
+    ("URL", Uuid::NAMESPACE_URL),
+    ("OID", Uuid::NAMESPACE_OID),
+    ("X500", Uuid::NAMESPACE_X500),
+];
+
+/// Resolves a namespace given as a preset name (`dns`, `url`, `oid`,
+/// `x500`, case-insensitive) or as a UUID, hyphenated or not.
+pub fn parse_namespace(input: &str) -> Result<Uuid, String> {
+    let input = input.trim();
+    if let Some((_, uuid)) = NAMESPACES.iter().find(|(name, _)| name.eq_ignore_ascii_case(input)) {
+        return Ok(*uuid);
+    }
+    Uuid::try_parse(input).map_err(|e| {
+        let presets: Vec<&str> = NAMESPACES.iter().map(|(name, _)| *name).collect();
+        format!("'{}' is neither a preset ({}) nor a UUID: {}", input, presets.join(", "), e)
+    })
+}
+
+/// The SHA-1 of the namespace's 16 bytes followed by the name's UTF-8
+/// bytes, before it is cut down to a UUID.
+pub fn name_digest(namespace: &Uuid, name: &str) -> [u8; 20] {
+    let mut hasher = Sha1::new();
+    hasher.update(namespace.as_bytes());
+    hasher.update(name.as_bytes());
+    hasher.finalize().into()
+}
+
+/// A name-based UUID, version 5: the first 16 bytes of
+/// `SHA-1(namespace || name)` with the version nibble set to 5 and the
+/// variant bits to `10`. The same namespace and name always give the same
+/// UUID, so independent systems derive matching IDs without coordinating.
+///
+/// SHA-1's broken collision resistance doesn't matter for naming, but the
+/// UUID hides nothing: anyone can recompute it from a guessed name.
+pub fn uuid_v5(namespace: &Uuid, name: &str) -> Uuid {
+    let digest = name_digest(namespace, name);
+    Builder::from_sha1_bytes(digest[..16].try_into().unwrap()).into_uuid()
+}
+
+fn read_namespace() -> Uuid {
+    let mut namespace_choices: Vec<String> = NAMESPACES
+        .iter()
+        .map(|(name, uuid)| format!("{} ({})", name, uuid))
+        .collect();
+    namespace_choices.push("Custom namespace UUID".to_string());
+    let namespace_selection = Select::new()
+        .with_prompt("Namespace")
+        .items(&namespace_choices)
+        .default(0)
+        .interact()
+        .unwrap();
+    if let Some((_, uuid)) = NAMESPACES.get(namespace_selection) {
+        return *uuid;
+    }
+
+    let namespace: String = Input::new()
+        .with_prompt("Namespace UUID")
+        .validate_with(|namespace: &String| Uuid::try_parse(namespace.trim()).map(|_| ()).map_err(|e| e.to_string()))
+        .interact_text()
+        .unwrap();
+    Uuid::try_parse(namespace.trim()).unwrap()
+}
+
+pub fn uuid_v5_interactive() {
+    let namespace = read_namespace();
+    let name: String = Input::new()
+        .with_prompt("Name")
+        .default("example.com".to_string())
+        .allow_empty(true)
+        .interact_text()
+        .unwrap();
+
+    let digest = name_digest(&namespace, &name);
+    let uuid = uuid_v5(&namespace, &name);
+
+    println!("\nNamespace: {}", namespace);
+    println!("Name: '{}'", name);
+    println!("SHA-1(namespace || name): {}", encode(digest));
+    println!("UUIDv5: {}\n", uuid);
+    println!("The first 16 bytes of the SHA-1, with the version nibble (the 13th hex");
+    println!("digit) set to 5 and the top bits of the 17th set to the RFC variant.\n");
+}
@@ -0,0 +1,43 @@
+use clap::ValueEnum;
+
+use crate::algorithms::{hash_bytes, ALGORITHMS};
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum VectorFormat {
+    Markdown,
+    Plain,
+}
+
+/// The inputs NIST uses for its SHA examples: empty, one block, a
+/// two-block message, and one million repetitions of "a".
+fn vector_inputs() -> Vec<(String, Vec<u8>)> {
+    let two_block = "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+    vec![
+        ("\"\"".to_string(), Vec::new()),
+        ("\"abc\"".to_string(), b"abc".to_vec()),
+        (format!("\"{}\"", two_block), two_block.as_bytes().to_vec()),
+        ("\"a\" x 1,000,000".to_string(), vec![b'a'; 1_000_000]),
+    ]
+}
+
+/// Prints every compiled-in algorithm's digest of the standard inputs, for
+/// pasting into documentation or tests and for checking against published
+/// vectors.
+pub fn print_vectors(format: VectorFormat) {
+    let inputs = vector_inputs();
+
+    if let VectorFormat::Markdown = format {
+        println!("| Algorithm | Input | Digest |");
+        println!("|-----------|-------|--------|");
+    }
+
+    for algorithm in ALGORITHMS {
+        for (label, input) in &inputs {
+            let digest = hash_bytes(input, algorithm).expect("built-in algorithms always hash");
+            match format {
+                VectorFormat::Markdown => println!("| {} | `{}` | `{}` |", algorithm, label, digest),
+                VectorFormat::Plain => println!("{}({}) = {}", algorithm, label, digest),
+            }
+        }
+    }
+}
@@ -0,0 +1,471 @@
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use dialoguer::{Input, Select};
+use indicatif::{ProgressBar, ProgressStyle};
+use regex::Regex;
+
+use crate::algorithms::{self, hash_file, hash_text, select_algorithm};
+use crate::audit;
+use crate::cache::{hash_file_cached, HashCache};
+use crate::interrupt;
+use crate::manifest::{list_files, read_manifest};
+use crate::records::read_full;
+
+pub fn verification() {
+    #[allow(unused_mut)]
+    let mut verify_choices = vec![
+        "Verify Hash in Filename",
+        "Verify Directory Against Manifest",
+        "Verify OCI Digest (sha256:...)",
+        "Per-Block CRC32 (Resumable Transfer)",
+        "Proof of Retrievability (Challenge-Response)",
+    ];
+    #[cfg(feature = "remote")]
+    verify_choices.push("Verify Against Published Checksums (URL)");
+    let verify_selection = Select::new()
+        .with_prompt("Choose a verification mode")
+        .items(&verify_choices)
+        .default(0)
+        .interact()
+        .unwrap();
+
+    match verify_selection {
+        0 => verify_filename_hash(),
+        1 => verify_directory_interactive(),
+        2 => verify_oci_digest(),
+        3 => block_crcs_interactive(),
+        4 => crate::retrievability::proof_of_retrievability(),
+        #[cfg(feature = "remote")]
+        5 => verify_remote_interactive(),
+        _ => unreachable!(),
+    }
+}
+
+/// Checks a file against a hex digest embedded in its own name, as used by
+/// content-addressed downloads like `artifact-<sha256>.tar.gz`.
+fn verify_filename_hash() {
+    let file_path: String = Input::new()
+        .with_prompt("Enter file path to verify")
+        .interact_text()
+        .unwrap();
+
+    let algorithm = select_algorithm();
+
+    // The first capture group (or the whole match) is taken as the digest.
+    let digest_len = match hash_text("", algorithm) {
+        Ok(empty) => empty.len(),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return;
+        }
+    };
+    let pattern: String = Input::new()
+        .with_prompt("Filename pattern (regex)")
+        .default(format!("([0-9a-fA-F]{{{}}})", digest_len))
+        .interact_text()
+        .unwrap();
+
+    match check_filename_hash(&file_path, algorithm, &pattern) {
+        Ok((expected, actual)) => {
+            println!("\nFile: '{}'", file_path);
+            println!("Algorithm: {}", algorithm);
+            println!("Expected (from filename): {}", expected);
+            println!("Actual:                   {}", actual);
+            println!();
+
+            if expected == actual {
+                println!("MATCH: file contents agree with the digest in its name.\n");
+            } else {
+                println!("MISMATCH: file contents do not agree with the digest in its name.\n");
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+        }
+    }
+}
+
+fn check_filename_hash(
+    file_path: &str,
+    algorithm: &str,
+    pattern: &str,
+) -> Result<(String, String), Box<dyn std::error::Error>> {
+    let regex = Regex::new(pattern).map_err(|e| format!("Invalid pattern: {}", e))?;
+
+    let file_name = Path::new(file_path)
+        .file_name()
+        .ok_or_else(|| format!("'{}' has no file name", file_path))?
+        .to_string_lossy();
+
+    let captures = regex
+        .captures(&file_name)
+        .ok_or_else(|| format!("Pattern did not match file name '{}'", file_name))?;
+    let expected = captures.get(1).or_else(|| captures.get(0)).unwrap().as_str();
+
+    if expected.is_empty() || !expected.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Extracted '{}' is not a hex digest", expected).into());
+    }
+
+    let actual = hash_file(file_path, algorithm)?;
+    audit::record(algorithm, file_path, &actual);
+    Ok((expected.to_ascii_lowercase(), actual))
+}
+
+#[cfg(feature = "remote")]
+fn verify_remote_interactive() {
+    let file_path: String = Input::new()
+        .with_prompt("Enter file path to verify")
+        .interact_text()
+        .unwrap();
+    let url: String = Input::new()
+        .with_prompt("Checksum file URL (e.g. .../SHA256SUMS)")
+        .interact_text()
+        .unwrap();
+    let algorithm = select_algorithm();
+
+    match crate::remote::check_remote(&file_path, &url, algorithm) {
+        Ok(check) => {
+            println!("\nFile: '{}'", file_path);
+            println!("Entry: '{}'", check.entry);
+            println!("Algorithm: {}", check.algorithm);
+            println!("Published: {}", check.expected);
+            println!("Actual:    {}", check.actual);
+            println!();
+
+            if check.matches() {
+                println!("MATCH: the file agrees with the published checksum.\n");
+            } else {
+                println!("MISMATCH: the file does not agree with the published checksum.\n");
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+        }
+    }
+}
+
+/// Checks a blob against an OCI content digest such as those printed by
+/// `docker images --digests`. For an image that digest covers its manifest
+/// JSON, byte for byte; for a layer it covers the compressed layer tarball.
+fn verify_oci_digest() {
+    let file_path: String = Input::new()
+        .with_prompt("Enter file path (manifest JSON or layer blob)")
+        .interact_text()
+        .unwrap();
+
+    let reference: String = Input::new()
+        .with_prompt("Expected digest (sha256:<hex>, or name@sha256:<hex>)")
+        .interact_text()
+        .unwrap();
+
+    let expected = match parse_oci_digest(&reference) {
+        Ok(expected) => expected,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return;
+        }
+    };
+
+    match hash_file(&file_path, "SHA-256") {
+        Ok(actual) => {
+            audit::record("SHA-256", &file_path, &actual);
+            let actual = format!("sha256:{}", actual);
+            println!("\nFile: '{}'", file_path);
+            println!("Expected: {}", expected);
+            println!("Actual:   {}", actual);
+            println!();
+            if expected == actual {
+                println!("MATCH: the blob has the expected OCI digest.\n");
+            } else {
+                println!("MISMATCH: the blob does not have the expected OCI digest.\n");
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+        }
+    }
+}
+
+/// Validates an OCI digest string, `algorithm ":" encoded` per the image
+/// spec, and returns it in canonical form. Anything before an `@` is taken to
+/// be an image name and dropped. Only `sha256` is supported; the spec
+/// requires its 64 hex digits to be lowercase.
+fn parse_oci_digest(reference: &str) -> Result<String, String> {
+    let digest = reference.trim().rsplit('@').next().unwrap();
+    let (algorithm, encoded) = digest
+        .split_once(':')
+        .ok_or_else(|| format!("'{}' is not a digest; expected sha256:<hex>", digest))?;
+
+    if algorithm != "sha256" {
+        return Err(format!("unsupported digest algorithm '{}'; only sha256 is supported", algorithm));
+    }
+    if encoded.len() != 64 || !encoded.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f')) {
+        return Err("a sha256 digest must be exactly 64 lowercase hex digits".into());
+    }
+    Ok(digest.to_string())
+}
+
+/// Default block size for per-block CRCs: small enough that a bad block is
+/// cheap to resend, large enough that the list stays short.
+const DEFAULT_CRC_BLOCK: usize = 64 * 1024;
+
+/// Lists the CRC32 of every fixed-size block of a file, one `index offset
+/// crc` line each, so the receiver of a transfer can check its copy block by
+/// block and ask for only the bad blocks again, the first step of an
+/// rsync-style delta transfer. Given the received copy as well, the blocks
+/// that differ are listed directly.
+fn block_crcs_interactive() {
+    let path: String = Input::new()
+        .with_prompt("Enter file path (the sender's copy)")
+        .interact_text()
+        .unwrap();
+    let block_size: usize = Input::new()
+        .with_prompt("Block size in bytes")
+        .default(DEFAULT_CRC_BLOCK)
+        .validate_with(|size: &usize| if *size > 0 { Ok(()) } else { Err("must be at least 1") })
+        .interact_text()
+        .unwrap();
+    let received: String = Input::new()
+        .with_prompt("Received copy to check (empty to only list CRCs)")
+        .allow_empty(true)
+        .interact_text()
+        .unwrap();
+
+    let sent = match block_crcs(&path, block_size) {
+        Ok(crcs) => crcs,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return;
+        }
+    };
+
+    println!("\nFile: '{}'", path);
+    println!("Block size: {} bytes, {} blocks", block_size, sent.len());
+    println!();
+    println!("{:>8}  {:>12}  CRC32", "block", "offset");
+    for (index, crc) in sent.iter().enumerate() {
+        println!("{:>8}  {:>12}  {:08x}", index, index * block_size, crc);
+    }
+    println!();
+
+    if received.trim().is_empty() {
+        return;
+    }
+    match block_crcs(received.trim(), block_size) {
+        Ok(got) => {
+            let bad = bad_blocks(&sent, &got);
+            println!("Received: '{}', {} blocks", received.trim(), got.len());
+            if bad.is_empty() {
+                println!("All blocks match; nothing to resend.\n");
+            } else {
+                let list: Vec<String> = bad.iter().map(|index| index.to_string()).collect();
+                println!("Resend {} of {} blocks: {}\n", bad.len(), sent.len(), list.join(", "));
+            }
+        }
+        Err(e) => eprintln!("Error: {}", e),
+    }
+    println!("CRC32 catches line noise and truncation, not tampering; check the whole file");
+    println!("with a cryptographic hash once every block is in.\n");
+}
+
+/// The CRC32 of each `block_size` bytes of the file; the last block may be
+/// shorter.
+pub fn block_crcs(path: &str, block_size: usize) -> Result<Vec<u32>, Box<dyn std::error::Error>> {
+    let mut file = fs::File::open(path).map_err(|e| format!("Cannot open '{}': {}", path, e))?;
+    let mut buffer = vec![0u8; block_size];
+    let mut crcs = Vec::new();
+    loop {
+        let read = read_full(&mut file, &mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        crcs.push(crc32fast::hash(&buffer[..read]));
+        if read < block_size {
+            break;
+        }
+    }
+    Ok(crcs)
+}
+
+/// Indexes of the sender's blocks the receiver doesn't have intact: those
+/// whose CRC differs, and those past the end of a truncated copy.
+fn bad_blocks(sent: &[u32], received: &[u32]) -> Vec<usize> {
+    (0..sent.len()).filter(|&index| received.get(index) != Some(&sent[index])).collect()
+}
+
+/// Outcome of re-hashing a directory against a saved manifest.
+#[derive(Default)]
+pub struct DirectoryReport {
+    pub ok: usize,
+    pub changed: Vec<String>,
+    pub missing: Vec<String>,
+    pub new: Vec<String>,
+    pub unreadable: Vec<(String, String)>,
+    /// Files left unchecked because the user pressed Ctrl-C.
+    pub skipped: usize,
+}
+
+impl DirectoryReport {
+    pub fn passed(&self) -> bool {
+        self.changed.is_empty() && self.missing.is_empty() && self.new.is_empty() && self.unreadable.is_empty()
+    }
+
+    pub fn print(&self) {
+        for path in &self.changed {
+            println!("CHANGED  {}", path);
+        }
+        for path in &self.missing {
+            println!("MISSING  {}", path);
+        }
+        for path in &self.new {
+            println!("NEW      {}", path);
+        }
+        for (path, error) in &self.unreadable {
+            println!("ERROR    {}: {}", path, error);
+        }
+
+        println!(
+            "\nOK: {}, Changed: {}, Missing: {}, New: {}, Unreadable: {}",
+            self.ok,
+            self.changed.len(),
+            self.missing.len(),
+            self.new.len(),
+            self.unreadable.len()
+        );
+        if self.skipped > 0 {
+            println!("Interrupted: {} file(s) were not checked.", self.skipped);
+        }
+        if self.passed() && self.skipped == 0 {
+            println!("Directory matches the manifest.");
+        } else if !self.passed() {
+            println!("Directory does NOT match the manifest.");
+        }
+    }
+}
+
+fn verify_directory_interactive() {
+    let dir: String = Input::new()
+        .with_prompt("Enter directory to verify")
+        .interact_text()
+        .unwrap();
+
+    let manifest_path: String = Input::new()
+        .with_prompt("Enter manifest path")
+        .default("manifest.txt".to_string())
+        .interact_text()
+        .unwrap();
+
+    match verify_directory(&dir, &manifest_path, None, None) {
+        Ok(report) => {
+            println!();
+            report.print();
+            println!();
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+        }
+    }
+}
+
+/// Re-hashes every file under `dir` with the manifest's algorithm (or
+/// `algorithm` when the manifest has no header) and sorts each path into
+/// OK/changed/missing/new. Hashing is spread across all CPUs with a progress
+/// bar; Ctrl-C stops handing out work and returns what was checked so far.
+/// With a `cache`, files whose size and mtime are unchanged reuse their
+/// cached digest.
+pub fn verify_directory(
+    dir: &str,
+    manifest_path: &str,
+    algorithm: Option<&'static str>,
+    cache: Option<&Mutex<HashCache>>,
+) -> Result<DirectoryReport, Box<dyn std::error::Error>> {
+    let manifest = read_manifest(manifest_path)?;
+    let algorithm = match (&manifest.algorithm, algorithm) {
+        (Some(name), _) => algorithms::lookup(name)?,
+        (None, Some(algorithm)) => algorithm,
+        (None, None) => select_algorithm(),
+    };
+
+    let root = Path::new(dir);
+    if !root.is_dir() {
+        return Err(format!("'{}' is not a directory", dir).into());
+    }
+
+    // A manifest stored inside the tree it describes is not part of it.
+    let manifest_real = fs::canonicalize(manifest_path).ok();
+    let on_disk: Vec<String> = list_files(root)?
+        .into_iter()
+        .filter(|path| manifest_real.is_none() || fs::canonicalize(root.join(path)).ok() != manifest_real)
+        .collect();
+
+    let mut report = DirectoryReport::default();
+    let to_check: Vec<&String> = on_disk.iter().filter(|path| manifest.entries.contains_key(*path)).collect();
+    report.new = on_disk.iter().filter(|path| !manifest.entries.contains_key(*path)).cloned().collect();
+    report.missing = manifest
+        .entries
+        .keys()
+        .filter(|path| on_disk.binary_search(path).is_err())
+        .cloned()
+        .collect();
+
+    let progress = ProgressBar::new(to_check.len() as u64);
+    progress.set_style(
+        ProgressStyle::with_template("{bar:40} {pos}/{len} files ({eta} left)").unwrap(),
+    );
+
+    let _guard = interrupt::catch();
+    let next = AtomicUsize::new(0);
+    let workers = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            let sender = sender.clone();
+            let next = &next;
+            let to_check = &to_check;
+            scope.spawn(move || {
+                while !interrupt::interrupted() {
+                    let index = next.fetch_add(1, Ordering::SeqCst);
+                    let Some(path) = to_check.get(index) else { break };
+                    let full = root.join(path);
+                    let full = full.to_string_lossy();
+                    let result = match cache {
+                        Some(cache) => hash_file_cached(cache, &full, algorithm),
+                        None => hash_file(&full, algorithm),
+                    }
+                    .map_err(|e| e.to_string());
+                    if sender.send((index, result)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        for (index, result) in receiver {
+            let path = to_check[index];
+            match result {
+                Ok(hash) => {
+                    audit::record(algorithm, &root.join(path).to_string_lossy(), &hash);
+                    if &hash == manifest.entries.get(path).unwrap() {
+                        report.ok += 1;
+                    } else {
+                        report.changed.push(path.clone());
+                    }
+                }
+                Err(e) => report.unreadable.push((path.clone(), e)),
+            }
+            progress.inc(1);
+        }
+    });
+
+    progress.finish_and_clear();
+    report.changed.sort();
+    report.unreadable.sort();
+    report.skipped = to_check.len() - report.ok - report.changed.len() - report.unreadable.len();
+
+    Ok(report)
+}